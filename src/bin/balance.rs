@@ -0,0 +1,208 @@
+// Balance simulator: plays the same seeded headless runs for every
+// combination of the tuning knobs and reports how each one went.
+//
+//     cargo run --release --bin balance -- --runs 20 --difficulty hard \
+//         --weapon-damage 0.8,1,1.2 --zombie-health-per-wave 0,0.1 \
+//         --upgrade-cost 1,2 --csv balance.csv --json balance.json
+//
+// Without --csv or --json the CSV goes to stdout.
+
+use serde::Serialize;
+use zombie::headless::{self, HeadlessConfig, RunOutcome, RunReport};
+use zombie::plugins::settings::Balance;
+
+const USAGE: &str = "Usage: balance [--runs N] [--difficulty easy|medium|hard|endless] \
+                     [--mode clear|survival|defend|extraction] [--seed N] [--max-seconds N] \
+                     [--weapon-damage X,Y,..] [--zombie-health-per-wave X,Y,..] \
+                     [--upgrade-cost X,Y,..] [--csv PATH] [--json PATH]";
+
+const CSV_HEADER: &str = "weapon_damage,zombie_health_per_wave,upgrade_cost,runs,win_rate,\
+                          avg_seconds,avg_wave,avg_money_earned,avg_damage_taken,avg_time_to_kill";
+
+struct SweepConfig {
+    runs: HeadlessConfig,
+    weapon_damage: Vec<f32>,
+    zombie_health_per_wave: Vec<f32>,
+    upgrade_cost: Vec<f32>,
+    csv: Option<String>,
+    json: Option<String>,
+}
+
+impl SweepConfig {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let defaults = Balance::default();
+        let mut config = Self {
+            runs: HeadlessConfig::default(),
+            weapon_damage: vec![defaults.weapon_damage],
+            zombie_health_per_wave: vec![defaults.zombie_health_per_wave],
+            upgrade_cost: vec![defaults.upgrade_cost],
+            csv: None,
+            json: None,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for {}", arg))?;
+            match arg.as_str() {
+                "--weapon-damage" => config.weapon_damage = parse_list(arg, value)?,
+                "--zombie-health-per-wave" => {
+                    config.zombie_health_per_wave = parse_list(arg, value)?
+                }
+                "--upgrade-cost" => config.upgrade_cost = parse_list(arg, value)?,
+                "--csv" => config.csv = Some(value.clone()),
+                "--json" => config.json = Some(value.clone()),
+                _ => {
+                    if !config.runs.apply_arg(arg, value)? {
+                        return Err(format!("Unknown argument: {}", arg));
+                    }
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    // Every combination of the swept values
+    fn points(&self) -> Vec<Balance> {
+        let mut points = Vec::new();
+        for &weapon_damage in &self.weapon_damage {
+            for &zombie_health_per_wave in &self.zombie_health_per_wave {
+                for &upgrade_cost in &self.upgrade_cost {
+                    points.push(Balance {
+                        weapon_damage,
+                        zombie_health_per_wave,
+                        upgrade_cost,
+                    });
+                }
+            }
+        }
+        points
+    }
+}
+
+fn parse_list(arg: &str, value: &str) -> Result<Vec<f32>, String> {
+    value
+        .split(',')
+        .map(|item| {
+            item.trim()
+                .parse()
+                .map_err(|_| format!("Invalid value for {}: {}", arg, item))
+        })
+        .collect()
+}
+
+// One row of the report: a balance point and how its runs averaged out
+#[derive(Serialize)]
+struct SweepResult {
+    #[serde(flatten)]
+    balance: Balance,
+    runs: usize,
+    win_rate: f32,
+    avg_seconds: f32,
+    avg_wave: f32,
+    avg_money_earned: f32,
+    avg_damage_taken: f32,
+    // Weighted by kills, so short runs don't count as much as long ones
+    avg_time_to_kill: f32,
+}
+
+impl SweepResult {
+    fn new(balance: Balance, reports: &[RunReport]) -> Self {
+        let count = reports.len().max(1) as f32;
+        let average = |value: fn(&RunReport) -> f32| reports.iter().map(value).sum::<f32>() / count;
+        let wins = reports
+            .iter()
+            .filter(|report| report.outcome == RunOutcome::Won)
+            .count();
+        let kills: u32 = reports.iter().map(|report| report.kills).sum();
+        let time_to_kill_total: f32 = reports
+            .iter()
+            .map(|report| report.time_to_kill * report.kills as f32)
+            .sum();
+
+        Self {
+            balance,
+            runs: reports.len(),
+            win_rate: wins as f32 / count,
+            avg_seconds: average(|report| report.seconds),
+            avg_wave: average(|report| report.wave as f32),
+            avg_money_earned: average(|report| report.money_earned as f32),
+            avg_damage_taken: average(|report| report.damage_taken),
+            avg_time_to_kill: if kills > 0 {
+                time_to_kill_total / kills as f32
+            } else {
+                0.0
+            },
+        }
+    }
+
+    fn csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{:.3},{:.1},{:.2},{:.0},{:.1},{:.2}",
+            self.balance.weapon_damage,
+            self.balance.zombie_health_per_wave,
+            self.balance.upgrade_cost,
+            self.runs,
+            self.win_rate,
+            self.avg_seconds,
+            self.avg_wave,
+            self.avg_money_earned,
+            self.avg_damage_taken,
+            self.avg_time_to_kill
+        )
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let config = match SweepConfig::parse(&args) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let points = config.points();
+    let mut results = Vec::new();
+    for (index, balance) in points.iter().enumerate() {
+        // Same seeds for every point, so only the knobs differ
+        let reports = headless::play_runs(&config.runs, *balance);
+        let result = SweepResult::new(*balance, &reports);
+        eprintln!(
+            "[{}/{}] {:?}: {:.0}% wins, time to kill {:.2}s",
+            index + 1,
+            points.len(),
+            balance,
+            result.win_rate * 100.0,
+            result.avg_time_to_kill
+        );
+        results.push(result);
+    }
+
+    let csv = std::iter::once(CSV_HEADER.to_string())
+        .chain(results.iter().map(SweepResult::csv_row))
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n";
+
+    if config.csv.is_none() && config.json.is_none() {
+        print!("{}", csv);
+    }
+    if let Some(path) = &config.csv {
+        write_report(path, &csv);
+    }
+    if let Some(path) = &config.json {
+        let json = serde_json::to_string_pretty(&results).expect("Failed to serialize results");
+        write_report(path, &json);
+    }
+}
+
+fn write_report(path: &str, contents: &str) {
+    if let Err(err) = std::fs::write(path, contents) {
+        eprintln!("Failed to write {}: {}", path, err);
+        std::process::exit(1);
+    }
+    eprintln!("Wrote {}", path);
+}
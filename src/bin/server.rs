@@ -0,0 +1,94 @@
+// Dedicated server for online co-op: the whole game without a window,
+// waiting for clients that join from the lobby or with `zombie --connect HOST:PORT`.
+//
+//     cargo run --release --bin server -- --port 7777 --difficulty hard --mode survival
+//
+// A run starts as soon as someone joins and starts over a few seconds after
+// it ends. Players can come and go mid-run.
+
+use bevy::log::LogPlugin;
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+use zombie::headless::{self, HeadlessConfig};
+use zombie::network::DEFAULT_PORT;
+use zombie::plugins::net_server::{NetServer, NetServerPlugin};
+use zombie::plugins::settings::GameSettings;
+use zombie::plugins::simulation::TICK_RATE;
+
+const USAGE: &str = "Usage: server [--port N] [--difficulty easy|medium|hard|endless] \
+                     [--mode clear|survival|defend|extraction] [--seed N]";
+
+struct ServerConfig {
+    port: u16,
+    // Difficulty, mode and seed, read the same way as --headless
+    run: HeadlessConfig,
+    seed: Option<u64>,
+}
+
+impl ServerConfig {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut config = Self {
+            port: DEFAULT_PORT,
+            run: HeadlessConfig::default(),
+            seed: None,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for {}", arg))?;
+            let invalid = || format!("Invalid value for {}: {}", arg, value);
+            match arg.as_str() {
+                "--port" => config.port = value.parse().map_err(|_| invalid())?,
+                "--seed" => config.seed = Some(value.parse().map_err(|_| invalid())?),
+                "--difficulty" | "--mode" => {
+                    config.run.apply_arg(arg, value)?;
+                }
+                _ => return Err(format!("Unknown argument: {}", arg)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let config = match ServerConfig::parse(&args) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut app = headless::gameplay_app();
+    app.add_plugins((LogPlugin::default(), NetServerPlugin));
+    // No players of its own, everyone joins over the network
+    match NetServer::bind((Ipv4Addr::UNSPECIFIED, config.port).into(), 0) {
+        Ok(server) => app.insert_resource(server),
+        Err(err) => {
+            eprintln!("Failed to listen on port {}: {}", config.port, err);
+            std::process::exit(1);
+        }
+    };
+    let mut settings = app.world_mut().resource_mut::<GameSettings>();
+    settings.difficulty = Some(config.run.difficulty);
+    settings.game_mode = config.run.game_mode;
+    settings.seed = config.seed;
+
+    // Every update is one tick of game time, so pace them in real time
+    app.finish();
+    app.cleanup();
+    let tick = Duration::from_secs_f64(1.0 / TICK_RATE);
+    let mut next_tick = Instant::now();
+    loop {
+        app.update();
+        next_tick += tick;
+        match next_tick.checked_duration_since(Instant::now()) {
+            Some(wait) => std::thread::sleep(wait),
+            // Fell behind, don't try to catch up in a burst
+            None => next_tick = Instant::now(),
+        }
+    }
+}
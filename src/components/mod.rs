@@ -10,6 +10,9 @@ pub struct Projectile {
 #[derive(Component)]
 pub struct Zombie {
     pub money_reward: u32,
+    pub speed: f32,
+    // Per second while touching the player
+    pub damage: f32,
 }
 
 #[derive(Component)]
@@ -25,3 +28,26 @@ pub struct Wall;
 pub struct Collider {
     pub size: Vec2,
 }
+
+// A Wall segment that can be bought open to unlock the section behind it
+#[derive(Component)]
+pub struct Door {
+    pub cost: u32,
+    pub section: u32,
+}
+
+// Zombies only spawn from points whose section has been unlocked
+#[derive(Component)]
+pub struct SpawnPoint {
+    pub section: u32,
+    // Lets wave scripts target specific points
+    pub name: String,
+}
+
+// Everything spawned by the WorldPlugin, so the map can be rebuilt between runs
+#[derive(Component)]
+pub struct LevelGeometry;
+
+// Something zombies walk towards: the player, or an objective they have to protect
+#[derive(Component)]
+pub struct ZombieTarget;
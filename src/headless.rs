@@ -0,0 +1,363 @@
+use crate::GameplayPlugins;
+use crate::components::Zombie;
+use crate::plugins::bot::{Bot, BotPlugin};
+use crate::plugins::combat::{PlayerDamaged, ZombieKilled};
+use crate::plugins::game_mode::{GameMode, ModeState};
+use crate::plugins::settings::{Balance, Difficulty, GameSettings};
+use crate::plugins::simulation::TICK_RATE;
+use crate::plugins::wave::WaveState;
+use crate::states::{GameState, GameStatePlugin, RunTeardown};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::utils::HashMap;
+use std::time::Duration;
+
+// Runs the game logic without a window or GPU, with the bot playing, and
+// prints how each run went:
+//
+//     zombie --headless --runs 20 --difficulty hard --mode survival --seed 7
+//
+// Nothing is loaded from or written to the save files.
+
+const USAGE: &str = "Usage: zombie --headless [--runs N] [--difficulty easy|medium|hard|endless] \
+                     [--mode clear|survival|defend|extraction] [--seed N] [--max-seconds N]";
+
+#[derive(Resource, Debug, Clone)]
+pub struct HeadlessConfig {
+    pub runs: u32,
+    pub difficulty: Difficulty,
+    pub game_mode: GameMode,
+    // Run i plays with seed + i, so a batch can be repeated exactly
+    pub seed: u64,
+    // Game time after which a run is stopped (Endless can go on forever)
+    pub max_seconds: f32,
+}
+
+impl Default for HeadlessConfig {
+    fn default() -> Self {
+        Self {
+            runs: 10,
+            difficulty: Difficulty::Medium,
+            game_mode: GameMode::ClearWaves,
+            seed: 0,
+            max_seconds: 900.0,
+        }
+    }
+}
+
+impl HeadlessConfig {
+    // None unless --headless was passed. Bad arguments print the usage and exit.
+    pub fn from_args() -> Option<Self> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        if !args.iter().any(|arg| arg == "--headless") {
+            return None;
+        }
+
+        match Self::parse(&args) {
+            Ok(config) => Some(config),
+            Err(err) => {
+                eprintln!("{}\n{}", err, USAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut config = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--headless" {
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for {}", arg))?;
+            if !config.apply_arg(arg, value)? {
+                return Err(format!("Unknown argument: {}", arg));
+            }
+        }
+        Ok(config)
+    }
+
+    // Applies one `--flag value` pair. Ok(false) means the flag isn't ours,
+    // so other tools (the balance simulator) can add their own.
+    pub fn apply_arg(&mut self, arg: &str, value: &str) -> Result<bool, String> {
+        let invalid = || format!("Invalid value for {}: {}", arg, value);
+        match arg {
+            "--runs" => self.runs = value.parse().map_err(|_| invalid())?,
+            "--seed" => self.seed = value.parse().map_err(|_| invalid())?,
+            "--max-seconds" => self.max_seconds = value.parse().map_err(|_| invalid())?,
+            "--difficulty" => {
+                self.difficulty = match value {
+                    "easy" => Difficulty::Easy,
+                    "medium" => Difficulty::Medium,
+                    "hard" => Difficulty::Hard,
+                    "endless" => Difficulty::Endless,
+                    _ => return Err(invalid()),
+                }
+            }
+            "--mode" => {
+                self.game_mode = match value {
+                    "clear" => GameMode::ClearWaves,
+                    "survival" => GameMode::Survival,
+                    "defend" => GameMode::DefendGenerator,
+                    "extraction" => GameMode::Extraction,
+                    _ => return Err(invalid()),
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    Won,
+    Died,
+    TimedOut,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    pub seed: u64,
+    pub outcome: RunOutcome,
+    pub seconds: f32,
+    pub wave: u32,
+    pub money_earned: u32,
+    pub damage_taken: f32,
+    pub kills: u32,
+    // Average seconds from a zombie appearing to its death, 0 without kills
+    pub time_to_kill: f32,
+}
+
+#[derive(Resource, Default)]
+struct HeadlessRuns {
+    reports: Vec<RunReport>,
+    // Running totals for the run in progress
+    money_earned: u32,
+    damage_taken: f32,
+    kills: u32,
+    time_to_kill_total: f32,
+    // When each living zombie appeared, in run time
+    spawned_at: HashMap<Entity, f32>,
+}
+
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HeadlessRuns>()
+            .add_systems(
+                Update,
+                start_next_run.run_if(in_state(GameState::Dashboard)),
+            )
+            .add_systems(
+                Update,
+                (tally_run, stop_long_runs).run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), finish_run)
+            .add_systems(OnEnter(GameState::Win), finish_run);
+    }
+}
+
+// Plays the configured runs with the default balance and prints the results
+pub fn run(config: HeadlessConfig) {
+    let reports = play_runs(&config, Balance::default());
+    print_reports(&config, &reports);
+}
+
+// Plays the configured runs as fast as possible with the bot at the controls
+pub fn play_runs(config: &HeadlessConfig, balance: Balance) -> Vec<RunReport> {
+    let mut app = gameplay_app();
+    app.add_plugins((BotPlugin, HeadlessPlugin))
+        .insert_resource(Bot { enabled: true })
+        .insert_resource(config.clone())
+        .insert_resource(balance);
+
+    app.finish();
+    app.cleanup();
+    while app.world().resource::<HeadlessRuns>().reports.len() < config.runs as usize {
+        app.update();
+    }
+
+    std::mem::take(&mut app.world_mut().resource_mut::<HeadlessRuns>().reports)
+}
+
+// The game without rendering, audio, UI or saving. Nobody is at the
+// controls until something drives `PlayerInput` or the keyboard.
+pub fn gameplay_app() -> App {
+    let mut app = windowless_app();
+    app.add_plugins(GameplayPlugins);
+    app
+}
+
+// States, input and the resources the dashboard normally provides, with no
+// gameplay plugins yet. Each update advances the game by one tick, however
+// fast the machine is.
+pub fn windowless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        bevy::state::app::StatesPlugin,
+        bevy::input::InputPlugin,
+        TransformPlugin,
+        HierarchyPlugin,
+    ))
+    .add_plugins(GameStatePlugin)
+    // Normally loaded from the profile and the replay recorder
+    .init_resource::<crate::plugins::progression::GlobalPlayerStats>()
+    .init_resource::<crate::plugins::replay::ReplayState>()
+    .add_systems(RunTeardown, crate::plugins::dashboard::cleanup_level)
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1.0 / TICK_RATE,
+    )));
+    app
+}
+
+fn print_reports(config: &HeadlessConfig, reports: &[RunReport]) {
+    println!(
+        "{} runs, {:?} difficulty, {} mode",
+        reports.len(),
+        config.difficulty,
+        config.game_mode.label()
+    );
+    println!("seed,outcome,seconds,wave,money_earned,damage_taken,kills,time_to_kill");
+    for report in reports {
+        println!(
+            "{},{:?},{:.1},{},{},{:.1},{},{:.2}",
+            report.seed,
+            report.outcome,
+            report.seconds,
+            report.wave,
+            report.money_earned,
+            report.damage_taken,
+            report.kills,
+            report.time_to_kill
+        );
+    }
+
+    if reports.is_empty() {
+        return;
+    }
+    let count = reports.len() as f32;
+    let wins = reports
+        .iter()
+        .filter(|report| report.outcome == RunOutcome::Won)
+        .count();
+    let average = |value: fn(&RunReport) -> f32| reports.iter().map(value).sum::<f32>() / count;
+    println!(
+        "wins {}/{} | avg {:.1}s | avg wave {:.1} | avg money {:.0} | avg damage {:.1}",
+        wins,
+        reports.len(),
+        average(|report| report.seconds),
+        average(|report| report.wave as f32),
+        average(|report| report.money_earned as f32),
+        average(|report| report.damage_taken)
+    );
+}
+
+fn start_next_run(
+    config: Res<HeadlessConfig>,
+    mut runs: ResMut<HeadlessRuns>,
+    mut settings: ResMut<GameSettings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let index = runs.reports.len() as u64;
+    if index >= u64::from(config.runs) {
+        return;
+    }
+
+    settings.difficulty = Some(config.difficulty);
+    settings.game_mode = config.game_mode;
+    settings.seed = Some(config.seed + index);
+    runs.money_earned = 0;
+    runs.damage_taken = 0.0;
+    runs.kills = 0;
+    runs.time_to_kill_total = 0.0;
+    runs.spawned_at.clear();
+    next_state.set(GameState::Playing);
+}
+
+fn tally_run(
+    mut runs: ResMut<HeadlessRuns>,
+    mode_state: Res<ModeState>,
+    spawned: Query<Entity, Added<Zombie>>,
+    mut kills: EventReader<ZombieKilled>,
+    mut damage: EventReader<PlayerDamaged>,
+) {
+    for zombie in &spawned {
+        runs.spawned_at.insert(zombie, mode_state.elapsed);
+    }
+    for kill in kills.read() {
+        runs.money_earned += kill.reward;
+        if let Some(spawned_at) = runs.spawned_at.remove(&kill.zombie) {
+            runs.kills += 1;
+            runs.time_to_kill_total += mode_state.elapsed - spawned_at;
+        }
+    }
+    for event in damage.read() {
+        runs.damage_taken += event.amount;
+    }
+}
+
+fn stop_long_runs(
+    config: Res<HeadlessConfig>,
+    mode_state: Res<ModeState>,
+    mut runs: ResMut<HeadlessRuns>,
+    settings: Res<GameSettings>,
+    wave_state: Res<WaveState>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if mode_state.elapsed >= config.max_seconds {
+        record_run(
+            &mut runs,
+            RunOutcome::TimedOut,
+            &settings,
+            &mode_state,
+            &wave_state,
+        );
+        next_state.set(GameState::Dashboard);
+    }
+}
+
+fn finish_run(
+    state: Res<State<GameState>>,
+    mode_state: Res<ModeState>,
+    mut runs: ResMut<HeadlessRuns>,
+    settings: Res<GameSettings>,
+    wave_state: Res<WaveState>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let outcome = if *state.get() == GameState::Win {
+        RunOutcome::Won
+    } else {
+        RunOutcome::Died
+    };
+    record_run(&mut runs, outcome, &settings, &mode_state, &wave_state);
+    next_state.set(GameState::Dashboard);
+}
+
+fn record_run(
+    runs: &mut HeadlessRuns,
+    outcome: RunOutcome,
+    settings: &GameSettings,
+    mode_state: &ModeState,
+    wave_state: &WaveState,
+) {
+    let report = RunReport {
+        seed: settings.seed.unwrap_or_default(),
+        outcome,
+        seconds: mode_state.elapsed,
+        wave: wave_state.current_wave,
+        money_earned: runs.money_earned,
+        damage_taken: runs.damage_taken,
+        kills: runs.kills,
+        time_to_kill: if runs.kills > 0 {
+            runs.time_to_kill_total / runs.kills as f32
+        } else {
+            0.0
+        },
+    };
+    runs.reports.push(report);
+}
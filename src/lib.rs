@@ -0,0 +1,114 @@
+#![allow(clippy::type_complexity, clippy::too_many_arguments)]
+
+// Zombie Terminate as a library. The binary, the balance simulator and the
+// integration tests all build their apps from the plugin groups below.
+
+pub mod components;
+pub mod headless;
+pub mod network;
+pub mod plugins;
+pub mod states;
+pub mod storage;
+pub mod systems;
+
+use bevy::app::PluginGroupBuilder;
+use bevy::prelude::*;
+use plugins::bot::BotPlugin;
+use plugins::camera_effects::CameraEffectsPlugin;
+use plugins::combat::CombatPlugin;
+use plugins::controls::ControlsPlugin;
+use plugins::custom_difficulty::CustomDifficultyPlugin;
+use plugins::daily_challenge::DailyChallengePlugin;
+use plugins::dashboard::DashboardPlugin;
+use plugins::doors::DoorsPlugin;
+use plugins::enemy::EnemyPlugin;
+use plugins::game_mode::GameModePlugin;
+use plugins::game_over::GameOverPlugin;
+use plugins::intermission::IntermissionPlugin;
+use plugins::keybindings::KeybindingsPlugin;
+use plugins::lobby::LobbyPlugin;
+use plugins::menu_navigation::MenuNavigationPlugin;
+use plugins::net_client::NetClientPlugin;
+use plugins::net_server::NetServerPlugin;
+use plugins::pause::PausePlugin;
+use plugins::player::PlayerPlugin;
+use plugins::progression::ProgressionPlugin;
+use plugins::replay::ReplayPlugin;
+use plugins::rng::RngPlugin;
+use plugins::run_modifiers::RunModifiersPlugin;
+use plugins::settings::SettingsPlugin;
+use plugins::shop::ShopPlugin;
+use plugins::simulation::SimulationPlugin;
+use plugins::spawning::SpawningPlugin;
+use plugins::touch::TouchControlsPlugin;
+use plugins::ui::UiPlugin;
+use plugins::view::ViewPlugin;
+use plugins::wave::WavePlugin;
+use plugins::world::WorldPlugin;
+use states::GameStatePlugin;
+
+// The types most callers need, without digging through the modules
+pub use plugins::controls::{Action, Keybindings, PlayerInput};
+pub use plugins::game_mode::GameMode;
+pub use plugins::player::{Loadout, Player, WeaponStats, WeaponType};
+pub use plugins::progression::GlobalPlayerStats;
+pub use plugins::settings::{Balance, Difficulty, GameSettings};
+pub use plugins::shop::Wallet;
+pub use plugins::wave::WaveState;
+pub use states::{DashboardScreen, GameState, WavePhase};
+
+// The whole game on top of DefaultPlugins. Sub-plugins can be swapped out
+// or dropped like any plugin group:
+//
+//     ZombieGamePlugin.build().disable::<ReplayPlugin>()
+pub struct ZombieGamePlugin;
+
+impl PluginGroup for ZombieGamePlugin {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(GameStatePlugin)
+            .add_group(GameplayPlugins)
+            .add(ViewPlugin)
+            .add(UiPlugin)
+            .add(GameOverPlugin)
+            .add(PausePlugin)
+            .add(DashboardPlugin)
+            .add(ProgressionPlugin)
+            .add(CameraEffectsPlugin)
+            .add(CustomDifficultyPlugin)
+            .add(DailyChallengePlugin)
+            .add(ReplayPlugin)
+            .add(KeybindingsPlugin)
+            .add(MenuNavigationPlugin)
+            .add(TouchControlsPlugin)
+            .add(BotPlugin)
+            .add(NetServerPlugin)
+            .add(NetClientPlugin)
+            .add(LobbyPlugin)
+    }
+}
+
+// The simulation only: no rendering, UI, menus or saving. Runs without a
+// window, which is how the headless mode and the tests use it.
+pub struct GameplayPlugins;
+
+impl PluginGroup for GameplayPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(SettingsPlugin)
+            .add(ControlsPlugin)
+            .add(SimulationPlugin)
+            .add(RngPlugin)
+            .add(RunModifiersPlugin)
+            .add(WorldPlugin)
+            .add(PlayerPlugin)
+            .add(CombatPlugin)
+            .add(EnemyPlugin)
+            .add(WavePlugin)
+            .add(SpawningPlugin)
+            .add(ShopPlugin)
+            .add(DoorsPlugin)
+            .add(IntermissionPlugin)
+            .add(GameModePlugin)
+    }
+}
@@ -1,52 +1,51 @@
-#![allow(clippy::type_complexity)]
 use bevy::prelude::*;
+use std::net::{SocketAddr, ToSocketAddrs};
+use zombie::plugins::net_client::NetClient;
+use zombie::{ZombieGamePlugin, headless};
 
-mod components;
-mod plugins;
-mod systems;
+fn main() {
+    // `--headless` plays scripted runs without a window and prints the results
+    if let Some(config) = headless::HeadlessConfig::from_args() {
+        headless::run(config);
+        return;
+    }
 
-use plugins::combat::CombatPlugin;
-use plugins::dashboard::DashboardPlugin;
-use plugins::enemy::EnemyPlugin;
-use plugins::game_over::GameOverPlugin;
-use plugins::pause::PausePlugin;
-use plugins::player::PlayerPlugin;
-use plugins::progression::ProgressionPlugin;
-use plugins::settings::SettingsPlugin;
-use plugins::shop::ShopPlugin;
-use plugins::ui::UiPlugin;
-use plugins::wave::WavePlugin;
-use plugins::world::WorldPlugin;
-use systems::camera::setup_camera;
-use systems::rendering::y_sort;
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: "Zombie Terminate".into(),
+            resolution: (1280.0, 720.0).into(),
+            ..default()
+        }),
+        ..default()
+    }))
+    .add_plugins(ZombieGamePlugin);
 
-mod states;
-use states::GameState;
+    // `--connect HOST:PORT` skips the menus and joins a server straight
+    // away, like Join in the lobby
+    if let Some(server) = server_from_args() {
+        match NetClient::connect(server) {
+            Ok(client) => app.insert_resource(client),
+            Err(err) => {
+                eprintln!("Failed to open a socket: {}", err);
+                std::process::exit(1);
+            }
+        };
+    }
 
-fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Zombie Terminate".into(),
-                resolution: (1280.0, 720.0).into(),
-                ..default()
-            }),
-            ..default()
-        }))
-        .init_state::<GameState>()
-        .add_plugins(PlayerPlugin)
-        .add_plugins(CombatPlugin)
-        .add_plugins(EnemyPlugin)
-        .add_plugins(WavePlugin)
-        .add_plugins(UiPlugin)
-        .add_plugins(WorldPlugin)
-        .add_plugins(ShopPlugin)
-        .add_plugins(GameOverPlugin)
-        .add_plugins(PausePlugin)
-        .add_plugins(DashboardPlugin)
-        .add_plugins(ProgressionPlugin)
-        .add_plugins(SettingsPlugin)
-        .add_systems(Startup, setup_camera)
-        .add_systems(Update, y_sort)
-        .run();
+    app.run();
+}
+
+fn server_from_args() -> Option<SocketAddr> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let index = args.iter().position(|arg| arg == "--connect")?;
+    let resolved = args
+        .get(index + 1)
+        .and_then(|address| address.to_socket_addrs().ok())
+        .and_then(|mut addresses| addresses.next());
+    if resolved.is_none() {
+        eprintln!("Usage: zombie --connect HOST:PORT");
+        std::process::exit(2);
+    }
+    resolved
 }
@@ -0,0 +1,185 @@
+use crate::plugins::controls::PlayerInput;
+use crate::plugins::game_mode::GameMode;
+use crate::plugins::settings::Difficulty;
+use crate::states::GameState;
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::net::{SocketAddr, UdpSocket};
+
+// What the server (dedicated or hosted) and its clients say to each other.
+// Every packet is one JSON message in one UDP datagram. Nothing is resent as
+// such: inputs go out every frame and snapshots carry the whole visible
+// state, so a lost packet is simply covered by the next one. Ready flags and
+// chat lines are repeated by the client until a snapshot shows them.
+
+pub const DEFAULT_PORT: u16 = 7777;
+// Largest datagram either side will read
+pub const MAX_PACKET: usize = 65_507;
+// Snapshots a second, clients blend between the last two
+pub const SNAPSHOT_RATE: f64 = 20.0;
+// Either side gives up on the other after this long without a packet
+pub const TIMEOUT_SECONDS: f32 = 5.0;
+// Chat lines kept in the session, older ones drop off
+pub const CHAT_LINES: usize = 8;
+// Longest chat line the server accepts, in characters
+pub const MAX_CHAT_LENGTH: usize = 80;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClientMessage {
+    // Sent until the server answers with Welcome or Full
+    Hello,
+    // This frame's input, one-shot presses included
+    Input(PlayerInput),
+    // Ready for the host to start
+    Ready(bool),
+    // Ids count up from 1 per client, so a resent line is only shown once
+    Chat { id: u32, text: String },
+    Goodbye,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ServerMessage {
+    // The player slot this client controls
+    Welcome { player: usize },
+    Full,
+    Snapshot(Snapshot),
+    // The host left, there's nothing more to join
+    Closed,
+}
+
+// Everything a client draws, as of one server tick
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub tick: u32,
+    pub state: GameState,
+    pub wave: u32,
+    pub money: u32,
+    pub objective: String,
+    // Door sections already bought, the client removes their doors
+    pub unlocked: Vec<u32>,
+    pub players: Vec<PlayerStatus>,
+    pub sprites: Vec<NetSprite>,
+    pub session: Session,
+}
+
+// The lobby as everyone sees it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Session {
+    // What the next run plays, picked by the host
+    pub difficulty: Option<Difficulty>,
+    pub game_mode: GameMode,
+    // Wing that starts open, None for just the courtyard
+    pub open_section: Option<u32>,
+    pub members: Vec<Member>,
+    // Oldest first, at most CHAT_LINES
+    pub chat: Vec<ChatLine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Member {
+    pub player: usize,
+    // Plays on the hosting machine, always ready
+    pub host: bool,
+    pub ready: bool,
+    // Id of the last chat line the server has from them
+    pub chat_id: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatLine {
+    // None for the server's own notices (joins and leaves)
+    pub player: Option<usize>,
+    pub text: String,
+}
+
+impl ChatLine {
+    pub fn describe(&self) -> String {
+        match self.player {
+            Some(player) => format!("P{}: {}", player + 1, self.text),
+            None => format!("* {}", self.text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerStatus {
+    pub player: usize,
+    pub health: f32,
+    pub max_health: f32,
+    pub ammo: u32,
+    pub max_ammo: u32,
+    pub downed: bool,
+}
+
+// One moving sprite (players, zombies, bullets, objectives). The map itself
+// is built the same way on both sides and isn't sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetSprite {
+    // Stable for the life of the entity on the server
+    pub id: u64,
+    // Set for players, with their slot
+    pub player: Option<usize>,
+    pub position: Vec2,
+    pub z: f32,
+    pub rotation: f32,
+    pub size: Vec2,
+    pub color: [f32; 4],
+}
+
+// A non-blocking UDP socket that speaks JSON messages
+pub struct NetSocket {
+    socket: UdpSocket,
+    buffer: Vec<u8>,
+}
+
+impl NetSocket {
+    pub fn bind(address: SocketAddr) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            buffer: vec![0; MAX_PACKET],
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.socket
+            .local_addr()
+            .expect("Bound socket has no address")
+    }
+
+    pub fn send<T: Serialize>(&self, to: SocketAddr, message: &T) {
+        let bytes = match serde_json::to_vec(message) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                warn!("Failed to encode packet: {}", err);
+                return;
+            }
+        };
+        if let Err(err) = self.socket.send_to(&bytes, to) {
+            warn!("Failed to send to {}: {}", to, err);
+        }
+    }
+
+    // The next waiting message, None once the socket is drained. Packets
+    // that don't parse are dropped.
+    pub fn receive<T: DeserializeOwned>(&mut self) -> Option<(SocketAddr, T)> {
+        loop {
+            let (length, from) = match self.socket.recv_from(&mut self.buffer) {
+                Ok(received) => received,
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => return None,
+                // A client that went away makes the next read fail on some systems
+                Err(err) if err.kind() == std::io::ErrorKind::ConnectionReset => continue,
+                Err(err) => {
+                    warn!("Failed to receive: {}", err);
+                    return None;
+                }
+            };
+            match serde_json::from_slice(&self.buffer[..length]) {
+                Ok(message) => return Some((from, message)),
+                Err(err) => warn!("Dropping bad packet from {}: {}", from, err),
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use crate::components::{Health, Zombie};
+use crate::plugins::controls::{PlayerInput, read_player_input};
+use crate::plugins::player::{Downed, Loadout, Player, WeaponStats, WeaponType};
+use crate::plugins::replay::watching_replay;
+use crate::plugins::shop::{Wallet, WeaponUpgradeCost};
+use crate::states::{GameState, WavePhase};
+use bevy::prelude::*;
+
+pub struct BotPlugin;
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Bot>()
+            .add_systems(Update, toggle_bot)
+            .add_systems(
+                PreUpdate,
+                drive_bot.after(read_player_input).run_if(
+                    in_state(GameState::Playing)
+                        .and_then(bot_enabled)
+                        .and_then(not(watching_replay)),
+                ),
+            );
+    }
+}
+
+// Debug key that hands the player over to the bot and back
+const TOGGLE_KEY: KeyCode = KeyCode::F9;
+// Zombies closer than this are run away from
+const KITE_DISTANCE: f32 = 120.0;
+// Holds fire beyond this, ammo only comes back between waves
+const FIRE_RANGE: f32 = 350.0;
+// Shotgun up close, rifle at range, pistol in between
+const SHOTGUN_RANGE: f32 = 150.0;
+const RIFLE_RANGE: f32 = 250.0;
+
+// Plays the game in place of the keyboard and mouse, for soak tests and the
+// headless simulator
+#[derive(Resource, Default)]
+pub struct Bot {
+    pub enabled: bool,
+}
+
+fn bot_enabled(bot: Res<Bot>) -> bool {
+    bot.enabled
+}
+
+fn toggle_bot(keyboard: Res<ButtonInput<KeyCode>>, mut bot: ResMut<Bot>) {
+    if keyboard.just_pressed(TOGGLE_KEY) {
+        bot.enabled = !bot.enabled;
+        info!("Bot {}", if bot.enabled { "On" } else { "Off" });
+    }
+}
+
+// Overwrites this frame's input for every player: faces the nearest zombie,
+// backs away from the ones that get close, picks the weapon for the range,
+// and reloads and shops between waves
+fn drive_bot(
+    mut player_query: Query<
+        (
+            &mut PlayerInput,
+            &Transform,
+            &Health,
+            &WeaponStats,
+            Has<Downed>,
+        ),
+        With<Player>,
+    >,
+    zombie_query: Query<&Transform, With<Zombie>>,
+    wave_phase: Res<State<WavePhase>>,
+    loadout: Res<Loadout>,
+    wallet: Res<Wallet>,
+    cost: Res<WeaponUpgradeCost>,
+) {
+    let zombies: Vec<Vec2> = zombie_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+
+    for (mut input, player_transform, health, stats, downed) in &mut player_query {
+        // Pausing stays with whoever is at the keyboard
+        *input = PlayerInput {
+            pause: input.pause,
+            ..default()
+        };
+        if downed {
+            continue;
+        }
+        drive_player(
+            &mut input,
+            player_transform.translation.truncate(),
+            health,
+            stats,
+            &zombies,
+            *wave_phase.get(),
+            &loadout,
+            &wallet,
+            &cost,
+        );
+    }
+}
+
+fn drive_player(
+    input: &mut PlayerInput,
+    player: Vec2,
+    health: &Health,
+    stats: &WeaponStats,
+    zombies: &[Vec2],
+    wave_phase: WavePhase,
+    loadout: &Loadout,
+    wallet: &Wallet,
+    cost: &WeaponUpgradeCost,
+) {
+    if wave_phase == WavePhase::Intermission {
+        input.reload = stats.current_ammo < stats.max_ammo;
+        input.buy_upgrade = wallet.money >= cost.fire_rate_cost;
+        input.buy_medkit = health.current < health.max / 2.0
+            && wallet.money >= cost.medkit_cost + cost.fire_rate_cost;
+        return;
+    }
+
+    let Some(nearest) = zombies.iter().copied().min_by(|a, b| {
+        a.distance_squared(player)
+            .total_cmp(&b.distance_squared(player))
+    }) else {
+        return;
+    };
+
+    let to_nearest = nearest - player;
+    let distance = to_nearest.length();
+    input.aim = Some(to_nearest.y.atan2(to_nearest.x));
+    input.fire = distance <= FIRE_RANGE && stats.current_ammo > 0;
+
+    // Closer zombies push harder, so the bot slips out between two of them
+    let away: Vec2 = zombies
+        .iter()
+        .map(|&zombie| player - zombie)
+        .filter(|offset| offset.length() < KITE_DISTANCE)
+        .map(|offset| offset.normalize_or_zero() * (KITE_DISTANCE - offset.length()))
+        .sum();
+    input.movement = away.normalize_or_zero();
+
+    let wanted = if distance < SHOTGUN_RANGE && loadout.unlocked_shotgun {
+        WeaponType::Shotgun
+    } else if distance > RIFLE_RANGE && loadout.unlocked_rifle {
+        WeaponType::Rifle
+    } else {
+        WeaponType::Pistol
+    };
+    if wanted != stats.current_weapon {
+        input.weapon = Some(wanted);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::plugins::combat::{PlayerDamaged, ZombieHit, ZombieKilled};
+use crate::plugins::player::{ShotFired, WeaponType};
+use crate::plugins::settings::GameSettings;
+use crate::systems::camera::{CameraRig, MainCamera, camera_follow};
+use bevy::prelude::*;
+
+pub struct CameraEffectsPlugin;
+
+impl Plugin for CameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraEffects>()
+            .add_systems(
+                Update,
+                collect_camera_effects.run_if(in_state(crate::states::GameState::Playing)),
+            )
+            .add_systems(Update, update_hit_stop)
+            .add_systems(
+                OnExit(crate::states::GameState::Playing),
+                clear_camera_effects,
+            )
+            .add_systems(
+                PostUpdate,
+                apply_camera_effects
+                    .after(camera_follow)
+                    .before(bevy::transform::TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+// Max camera offset (pixels) and roll (radians) at full trauma
+const MAX_SHAKE_OFFSET: f32 = 14.0;
+const MAX_SHAKE_ROLL: f32 = 0.04;
+// Trauma lost per second
+const TRAUMA_DECAY: f32 = 1.6;
+const ZOOM_PULSE_DECAY: f32 = 0.4;
+const HIT_STOP_SECONDS: f32 = 0.05;
+// Game speed while frozen (not 0.0 so timers still creep forward)
+const HIT_STOP_SPEED: f32 = 0.05;
+
+#[derive(Resource)]
+pub struct CameraEffects {
+    // 0..1, shake strength is trauma squared
+    pub trauma: f32,
+    // Extra zoom-in, decays back to 0
+    pub zoom_pulse: f32,
+    // Real-time timer so it isn't slowed by itself
+    pub hit_stop: Timer,
+}
+
+impl Default for CameraEffects {
+    fn default() -> Self {
+        let mut hit_stop = Timer::from_seconds(HIT_STOP_SECONDS, TimerMode::Once);
+        hit_stop.tick(hit_stop.duration()); // Start finished
+        Self {
+            trauma: 0.0,
+            zoom_pulse: 0.0,
+            hit_stop,
+        }
+    }
+}
+
+impl CameraEffects {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+
+    pub fn pulse_zoom(&mut self, amount: f32) {
+        self.zoom_pulse = (self.zoom_pulse + amount).min(0.15);
+    }
+}
+
+fn collect_camera_effects(
+    mut effects: ResMut<CameraEffects>,
+    mut time: ResMut<Time<Virtual>>,
+    settings: Res<GameSettings>,
+    replay: Res<crate::plugins::replay::ReplayState>,
+    mut shots: EventReader<ShotFired>,
+    mut hits: EventReader<ZombieHit>,
+    mut kills: EventReader<ZombieKilled>,
+    mut damage: EventReader<PlayerDamaged>,
+    rig_query: Query<&CameraRig, With<MainCamera>>,
+) {
+    let config = settings.camera_effects;
+    let focus = rig_query
+        .get_single()
+        .map(|rig| rig.focus)
+        .unwrap_or_default();
+    // Things happening far off-screen shake the camera less
+    let falloff = |position: Vec2| (1.0 - position.distance(focus) / 1000.0).clamp(0.3, 1.0);
+
+    for shot in shots.read() {
+        let kick = match shot.weapon {
+            WeaponType::Pistol => 0.05,
+            WeaponType::Shotgun => 0.2,
+            WeaponType::Rifle => 0.08,
+        };
+        effects.add_trauma(kick * config.shake_intensity);
+    }
+
+    for hit in hits.read() {
+        let trauma = hit.damage * 0.004 * falloff(hit.position);
+        effects.add_trauma(trauma * config.shake_intensity);
+    }
+
+    for kill in kills.read() {
+        // Bigger zombies are worth more and hit harder when they drop
+        let trauma = (0.15 + kill.reward as f32 * 0.005) * falloff(kill.position);
+        effects.add_trauma(trauma * config.shake_intensity);
+        if config.zoom_pulse {
+            effects.pulse_zoom(0.04);
+        }
+        // A replay's frame times already include the recorded slow-down
+        if config.hit_stop && !replay.is_playing_back() {
+            effects.hit_stop.reset();
+            time.set_relative_speed(HIT_STOP_SPEED);
+        }
+    }
+
+    // Damage is continuous, so this is trauma per point of health lost
+    for event in damage.read() {
+        effects.add_trauma(event.amount * 0.05 * config.shake_intensity);
+    }
+}
+
+fn update_hit_stop(
+    mut effects: ResMut<CameraEffects>,
+    mut time: ResMut<Time<Virtual>>,
+    real_time: Res<Time<Real>>,
+) {
+    if effects.hit_stop.finished() {
+        return;
+    }
+
+    effects.hit_stop.tick(real_time.delta());
+    if effects.hit_stop.finished() {
+        time.set_relative_speed(1.0);
+    }
+}
+
+fn apply_camera_effects(
+    mut effects: ResMut<CameraEffects>,
+    mut camera_query: Query<
+        (&mut Transform, &mut OrthographicProjection, &CameraRig),
+        With<MainCamera>,
+    >,
+    settings: Res<GameSettings>,
+    real_time: Res<Time<Real>>,
+) {
+    let dt = real_time.delta_seconds();
+    effects.trauma = (effects.trauma - TRAUMA_DECAY * dt).max(0.0);
+    effects.zoom_pulse = (effects.zoom_pulse - ZOOM_PULSE_DECAY * dt).max(0.0);
+
+    let Ok((mut transform, mut projection, rig)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    let shake = effects.trauma * effects.trauma * settings.camera_effects.shake_intensity;
+
+    // Cheap smooth noise: a few out-of-phase sines per axis
+    let t = real_time.elapsed_seconds() * 30.0;
+    let noise_x = (t * 1.0).sin() * 0.6 + (t * 2.3 + 1.7).sin() * 0.4;
+    let noise_y = (t * 1.3 + 4.1).sin() * 0.6 + (t * 2.9 + 0.3).sin() * 0.4;
+    let noise_roll = (t * 0.9 + 2.2).sin();
+
+    transform.translation.x = rig.focus.x + noise_x * MAX_SHAKE_OFFSET * shake;
+    transform.translation.y = rig.focus.y + noise_y * MAX_SHAKE_OFFSET * shake;
+    transform.rotation = Quat::from_rotation_z(noise_roll * MAX_SHAKE_ROLL * shake);
+
+    // Smaller scale = zoomed in
+    projection.scale = 1.0 - effects.zoom_pulse;
+}
+
+fn clear_camera_effects(mut effects: ResMut<CameraEffects>, mut time: ResMut<Time<Virtual>>) {
+    *effects = CameraEffects::default();
+    time.set_relative_speed(1.0);
+}
@@ -1,23 +1,50 @@
 use crate::components::Projectile;
+use crate::plugins::run_modifiers::{Mutator, mutator_active};
+use crate::plugins::simulation::GameplaySet;
 use bevy::prelude::*;
 
 pub struct CombatPlugin;
 
 impl Plugin for CombatPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                projectile_movement,
-                cleanup_projectiles,
-                projectile_collision,
-                zombie_damage_player,
-            )
-                .run_if(in_state(crate::states::GameState::Playing)),
-        );
+        app.add_event::<ZombieHit>()
+            .add_event::<ZombieKilled>()
+            .add_event::<PlayerDamaged>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    projectile_movement,
+                    cleanup_projectiles,
+                    projectile_collision,
+                    zombie_damage_player,
+                    vampirism_heal.run_if(mutator_active(Mutator::Vampirism)),
+                    zombie_explosions.run_if(mutator_active(Mutator::ExplosiveZombies)),
+                )
+                    .chain()
+                    .in_set(GameplaySet::Combat)
+                    .run_if(in_state(crate::states::GameState::Playing)),
+            );
     }
 }
 
+#[derive(Event)]
+pub struct ZombieHit {
+    pub position: Vec2,
+    pub damage: f32,
+}
+
+#[derive(Event)]
+pub struct ZombieKilled {
+    pub zombie: Entity,
+    pub position: Vec2,
+    pub reward: u32,
+}
+
+#[derive(Event)]
+pub struct PlayerDamaged {
+    pub amount: f32,
+}
+
 fn projectile_movement(time: Res<Time>, mut query: Query<(&mut Transform, &Projectile)>) {
     for (mut transform, projectile) in &mut query {
         transform.translation.x += projectile.velocity.x * time.delta_seconds();
@@ -40,6 +67,8 @@ fn projectile_collision(
     >,
     wall_query: Query<(&Transform, &crate::components::Collider), With<crate::components::Wall>>,
     mut wallet: ResMut<crate::plugins::shop::Wallet>,
+    mut hit_events: EventWriter<ZombieHit>,
+    mut killed_events: EventWriter<ZombieKilled>,
 ) {
     for (p_entity, p_transform, projectile) in &projectile_query {
         let mut hit_wall = false;
@@ -68,10 +97,19 @@ fn projectile_collision(
                 // Apply Damage
                 z_health.current -= projectile.damage;
                 commands.entity(p_entity).despawn();
+                hit_events.send(ZombieHit {
+                    position: z_transform.translation.truncate(),
+                    damage: projectile.damage,
+                });
 
                 if z_health.current <= 0.0 {
                     commands.entity(z_entity).despawn();
                     wallet.money += zombie_data.money_reward;
+                    killed_events.send(ZombieKilled {
+                        zombie: z_entity,
+                        position: z_transform.translation.truncate(),
+                        reward: zombie_data.money_reward,
+                    });
                     info!(
                         "Zombie Killed! +${}. Current Money: {}",
                         zombie_data.money_reward, wallet.money
@@ -99,28 +137,94 @@ fn cleanup_projectiles(
 fn zombie_damage_player(
     mut commands: Commands,
     mut player_query: Query<
-        (Entity, &Transform, &mut crate::components::Health),
-        With<crate::plugins::player::Player>,
+        (
+            Entity,
+            &crate::plugins::player::PlayerId,
+            &Transform,
+            &mut crate::components::Health,
+        ),
+        (
+            With<crate::plugins::player::Player>,
+            Without<crate::plugins::player::Downed>,
+        ),
     >,
-    zombie_query: Query<&Transform, With<crate::components::Zombie>>,
+    zombie_query: Query<(&Transform, &crate::components::Zombie)>,
     time: Res<Time>,
-    mut next_state: ResMut<NextState<crate::states::GameState>>,
+    mut damaged_events: EventWriter<PlayerDamaged>,
 ) {
-    if let Ok((p_entity, p_transform, mut p_health)) = player_query.get_single_mut() {
-        for z_transform in &zombie_query {
+    for (p_entity, p_id, p_transform, mut p_health) in &mut player_query {
+        for (z_transform, zombie) in &zombie_query {
             let distance = p_transform.translation.distance(z_transform.translation);
             if distance < 32.0 {
                 // Player size approx
                 // Continuous damage or instant hit? Let's do continuous for now (dps)
-                p_health.current -= 10.0 * time.delta_seconds(); // 10 DPS
+                let damage = zombie.damage * time.delta_seconds();
+                p_health.current -= damage;
+                damaged_events.send(PlayerDamaged { amount: damage });
 
                 if p_health.current <= 0.0 {
-                    // Player Death Logic
-                    info!("Player Died!");
-                    commands.entity(p_entity).despawn();
-                    next_state.set(crate::states::GameState::GameOver);
+                    crate::plugins::player::knock_down(&mut commands, p_entity, *p_id);
+                    break;
                 }
             }
         }
     }
 }
+
+const VAMPIRISM_HEAL: f32 = 3.0;
+const EXPLOSION_RADIUS: f32 = 70.0;
+const EXPLOSION_DAMAGE: f32 = 15.0;
+
+fn vampirism_heal(
+    mut killed_events: EventReader<ZombieKilled>,
+    mut player_query: Query<
+        &mut crate::components::Health,
+        (
+            With<crate::plugins::player::Player>,
+            Without<crate::plugins::player::Downed>,
+        ),
+    >,
+) {
+    let kills = killed_events.read().count();
+    for mut health in &mut player_query {
+        health.current = (health.current + VAMPIRISM_HEAL * kills as f32).min(health.max);
+    }
+}
+
+fn zombie_explosions(
+    mut commands: Commands,
+    mut killed_events: EventReader<ZombieKilled>,
+    mut player_query: Query<
+        (
+            Entity,
+            &crate::plugins::player::PlayerId,
+            &Transform,
+            &mut crate::components::Health,
+        ),
+        (
+            With<crate::plugins::player::Player>,
+            Without<crate::plugins::player::Downed>,
+        ),
+    >,
+    mut damaged_events: EventWriter<PlayerDamaged>,
+) {
+    let blasts: Vec<Vec2> = killed_events.read().map(|killed| killed.position).collect();
+
+    for (p_entity, p_id, p_transform, mut p_health) in &mut player_query {
+        for blast in &blasts {
+            if p_transform.translation.truncate().distance(*blast) > EXPLOSION_RADIUS {
+                continue;
+            }
+
+            p_health.current -= EXPLOSION_DAMAGE;
+            damaged_events.send(PlayerDamaged {
+                amount: EXPLOSION_DAMAGE,
+            });
+
+            if p_health.current <= 0.0 {
+                crate::plugins::player::knock_down(&mut commands, p_entity, *p_id);
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,519 @@
+use crate::plugins::player::{PlayerId, WeaponType};
+use bevy::ecs::system::SystemParam;
+use bevy::input::InputSystem;
+use bevy::input::mouse::MouseMotion;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Keybindings>()
+            .init_resource::<VirtualInput>()
+            .add_systems(PreUpdate, read_player_input.after(InputSystem));
+    }
+}
+
+// Everything that can be bound to a key, mouse or gamepad button
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Reload,
+    Pistol,
+    Shotgun,
+    Rifle,
+    // Steps through the unlocked weapons, for pads without number keys
+    NextWeapon,
+    Interact,
+    BuyUpgrade,
+    BuyMedkit,
+    Pause,
+    // Ready up between waves, leave the Win/Game Over screen
+    Confirm,
+    // Leave the run for the main menu
+    Back,
+}
+
+impl Action {
+    pub const ALL: [Action; 16] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Fire,
+        Action::Reload,
+        Action::Pistol,
+        Action::Shotgun,
+        Action::Rifle,
+        Action::NextWeapon,
+        Action::Interact,
+        Action::BuyUpgrade,
+        Action::BuyMedkit,
+        Action::Pause,
+        Action::Confirm,
+        Action::Back,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move Up",
+            Action::MoveDown => "Move Down",
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::Fire => "Fire",
+            Action::Reload => "Reload",
+            Action::Pistol => "Pistol",
+            Action::Shotgun => "Shotgun",
+            Action::Rifle => "Rifle",
+            Action::NextWeapon => "Next Weapon",
+            Action::Interact => "Open Door",
+            Action::BuyUpgrade => "Buy Fire Rate",
+            Action::BuyMedkit => "Buy Medkit",
+            Action::Pause => "Pause",
+            Action::Confirm => "Confirm",
+            Action::Back => "Back to Menu",
+        }
+    }
+
+    fn default_bindings(&self) -> Vec<Binding> {
+        use Binding::{Gamepad, Key, Mouse};
+        use GamepadButtonType as Pad;
+        match self {
+            Action::MoveUp => vec![Key(KeyCode::KeyW)],
+            Action::MoveDown => vec![Key(KeyCode::KeyS)],
+            Action::MoveLeft => vec![Key(KeyCode::KeyA)],
+            Action::MoveRight => vec![Key(KeyCode::KeyD)],
+            Action::Fire => vec![Mouse(MouseButton::Left), Gamepad(Pad::RightTrigger2)],
+            Action::Reload => vec![Key(KeyCode::KeyR), Gamepad(Pad::West)],
+            Action::Pistol => vec![Key(KeyCode::Digit1)],
+            Action::Shotgun => vec![Key(KeyCode::Digit2)],
+            Action::Rifle => vec![Key(KeyCode::Digit3)],
+            Action::NextWeapon => vec![Key(KeyCode::KeyQ), Gamepad(Pad::North)],
+            Action::Interact => vec![Key(KeyCode::KeyE), Gamepad(Pad::East)],
+            Action::BuyUpgrade => vec![Key(KeyCode::KeyB), Gamepad(Pad::DPadUp)],
+            Action::BuyMedkit => vec![Key(KeyCode::KeyH), Gamepad(Pad::DPadDown)],
+            Action::Pause => vec![Key(KeyCode::KeyP), Gamepad(Pad::Start)],
+            Action::Confirm => vec![
+                Key(KeyCode::Enter),
+                Key(KeyCode::Space),
+                Gamepad(Pad::South),
+            ],
+            Action::Back => vec![Key(KeyCode::Escape), Gamepad(Pad::Select)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    // Any connected pad
+    Gamepad(GamepadButtonType),
+}
+
+impl Binding {
+    pub fn is_gamepad(&self) -> bool {
+        matches!(self, Binding::Gamepad(_))
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{:?}", key);
+                match name
+                    .strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                {
+                    Some(short) => short.to_string(),
+                    None => name,
+                }
+            }
+            Binding::Mouse(MouseButton::Left) => "Left Click".to_string(),
+            Binding::Mouse(MouseButton::Right) => "Right Click".to_string(),
+            Binding::Mouse(MouseButton::Middle) => "Middle Click".to_string(),
+            Binding::Mouse(button) => format!("Mouse {:?}", button),
+            // Named after the Xbox layout
+            Binding::Gamepad(button) => match button {
+                GamepadButtonType::South => "A".to_string(),
+                GamepadButtonType::East => "B".to_string(),
+                GamepadButtonType::West => "X".to_string(),
+                GamepadButtonType::North => "Y".to_string(),
+                GamepadButtonType::LeftTrigger => "LB".to_string(),
+                GamepadButtonType::RightTrigger => "RB".to_string(),
+                GamepadButtonType::LeftTrigger2 => "LT".to_string(),
+                GamepadButtonType::RightTrigger2 => "RT".to_string(),
+                GamepadButtonType::LeftThumb => "L3".to_string(),
+                GamepadButtonType::RightThumb => "R3".to_string(),
+                GamepadButtonType::Select => "Select".to_string(),
+                GamepadButtonType::Start => "Start".to_string(),
+                GamepadButtonType::Mode => "Guide".to_string(),
+                GamepadButtonType::DPadUp => "D-Pad Up".to_string(),
+                GamepadButtonType::DPadDown => "D-Pad Down".to_string(),
+                GamepadButtonType::DPadLeft => "D-Pad Left".to_string(),
+                GamepadButtonType::DPadRight => "D-Pad Right".to_string(),
+                GamepadButtonType::Other(id) => format!("Pad {}", id),
+                other => format!("Pad {:?}", other),
+            },
+        }
+    }
+}
+
+// Which keys and buttons trigger each action. Rebound from the Controls
+// screen; a key or button can only belong to one action.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Keybindings {
+    bindings: BTreeMap<Action, Vec<Binding>>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .iter()
+                .map(|&action| (action, action.default_bindings()))
+                .collect(),
+        }
+    }
+}
+
+impl Keybindings {
+    // Actions added since the file was saved get their default keys, and
+    // saves from before pad support get the default pad buttons
+    pub fn fill_missing(mut self) -> Self {
+        for action in Action::ALL {
+            let bindings = self.bindings.entry(action).or_default();
+            if bindings.is_empty() {
+                *bindings = action.default_bindings();
+            } else if !bindings.iter().any(Binding::is_gamepad) {
+                bindings.extend(
+                    action
+                        .default_bindings()
+                        .into_iter()
+                        .filter(Binding::is_gamepad),
+                );
+            }
+        }
+        self
+    }
+
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    // The other action already using this key or button, if any
+    pub fn conflict(&self, binding: Binding, action: Action) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(other, bindings)| **other != action && bindings.contains(&binding))
+            .map(|(other, _)| *other)
+    }
+
+    // Replaces the action's bindings on the same device, so rebinding a key
+    // keeps the pad button and the other way round
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.bindings.entry(action).or_default();
+        bindings.retain(|other| other.is_gamepad() != binding.is_gamepad());
+        bindings.push(binding);
+    }
+
+    // "W", or "Enter / Space" when an action has several
+    pub fn describe(&self, action: Action) -> String {
+        let labels: Vec<String> = self.get(action).iter().map(Binding::label).collect();
+        if labels.is_empty() {
+            "Unbound".to_string()
+        } else {
+            labels.join(" / ")
+        }
+    }
+}
+
+// Stick positions closer to the centre than this count as released
+pub const STICK_DEADZONE: f32 = 0.2;
+
+// On-screen controls press actions and push sticks through here, so they
+// work everywhere the keyboard and pads do
+#[derive(Resource, Default)]
+pub struct VirtualInput {
+    pub buttons: ButtonInput<Action>,
+    // Up to unit length, y up like a pad stick
+    pub left_stick: Option<Vec2>,
+    pub right_stick: Option<Vec2>,
+}
+
+// Where a player's input comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputDevice {
+    // Everything at once, when playing alone
+    Any,
+    // Keyboard, mouse and the on-screen controls
+    KeyboardMouse,
+    Gamepad(Gamepad),
+}
+
+impl InputDevice {
+    fn desktop(&self) -> bool {
+        matches!(self, InputDevice::Any | InputDevice::KeyboardMouse)
+    }
+
+    fn has_pad(&self, pad: Gamepad) -> bool {
+        match self {
+            InputDevice::Any => true,
+            InputDevice::KeyboardMouse => false,
+            InputDevice::Gamepad(own) => *own == pad,
+        }
+    }
+}
+
+// Reads actions through the current keybindings, from the keyboard, the
+// mouse, every connected gamepad and the on-screen controls
+#[derive(SystemParam)]
+pub struct ActionInput<'w> {
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Res<'w, Gamepads>,
+    pad_buttons: Res<'w, ButtonInput<GamepadButton>>,
+    pad_axes: Res<'w, Axis<GamepadAxis>>,
+    bindings: Res<'w, Keybindings>,
+    virtual_input: Res<'w, VirtualInput>,
+}
+
+impl ActionInput<'_> {
+    // On any device, for menus and anything else not tied to one player
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed_on(action, InputDevice::Any)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed_on(action, InputDevice::Any)
+    }
+
+    pub fn pressed_on(&self, action: Action, device: InputDevice) -> bool {
+        (device.desktop() && self.virtual_input.buttons.pressed(action))
+            || self
+                .bindings
+                .get(action)
+                .iter()
+                .any(|binding| match binding {
+                    Binding::Key(key) => device.desktop() && self.keyboard.pressed(*key),
+                    Binding::Mouse(button) => device.desktop() && self.mouse.pressed(*button),
+                    Binding::Gamepad(button) => self
+                        .pads(device)
+                        .any(|pad| self.pad_buttons.pressed(GamepadButton::new(pad, *button))),
+                })
+    }
+
+    pub fn just_pressed_on(&self, action: Action, device: InputDevice) -> bool {
+        (device.desktop() && self.virtual_input.buttons.just_pressed(action))
+            || self
+                .bindings
+                .get(action)
+                .iter()
+                .any(|binding| match binding {
+                    Binding::Key(key) => device.desktop() && self.keyboard.just_pressed(*key),
+                    Binding::Mouse(button) => device.desktop() && self.mouse.just_pressed(*button),
+                    Binding::Gamepad(button) => self.pads(device).any(|pad| {
+                        self.pad_buttons
+                            .just_pressed(GamepadButton::new(pad, *button))
+                    }),
+                })
+    }
+
+    pub fn left_stick(&self, device: InputDevice) -> Option<Vec2> {
+        self.stick(
+            device,
+            GamepadAxisType::LeftStickX,
+            GamepadAxisType::LeftStickY,
+            self.virtual_input.left_stick,
+        )
+    }
+
+    pub fn right_stick(&self, device: InputDevice) -> Option<Vec2> {
+        self.stick(
+            device,
+            GamepadAxisType::RightStickX,
+            GamepadAxisType::RightStickY,
+            self.virtual_input.right_stick,
+        )
+    }
+
+    // The first pad (or on-screen stick) held past the deadzone, up to unit length
+    fn stick(
+        &self,
+        device: InputDevice,
+        x: GamepadAxisType,
+        y: GamepadAxisType,
+        on_screen: Option<Vec2>,
+    ) -> Option<Vec2> {
+        self.pads(device)
+            .filter_map(|pad| {
+                Some(Vec2::new(
+                    self.pad_axes.get(GamepadAxis::new(pad, x))?,
+                    self.pad_axes.get(GamepadAxis::new(pad, y))?,
+                ))
+            })
+            .chain(on_screen.filter(|_| device.desktop()))
+            .find(|stick| stick.length() > STICK_DEADZONE)
+            .map(|stick| stick.clamp_length_max(1.0))
+    }
+
+    // Connected pads in the order they were plugged in
+    fn pads(&self, device: InputDevice) -> impl Iterator<Item = Gamepad> + '_ {
+        let mut pads: Vec<Gamepad> = self.gamepads.iter().collect();
+        pads.sort_by_key(|pad| pad.id);
+        pads.into_iter().filter(move |pad| device.has_pad(*pad))
+    }
+
+    // Alone, a player can pick up any device. In co-op player 1 keeps the
+    // keyboard and mouse, and everyone else gets a pad in the order they
+    // were plugged in (None until theirs is).
+    pub fn device_for(&self, player: usize, players: usize) -> Option<InputDevice> {
+        match player {
+            _ if players <= 1 => Some(InputDevice::Any),
+            0 => Some(InputDevice::KeyboardMouse),
+            _ => self
+                .pads(InputDevice::Any)
+                .nth(player - 1)
+                .map(InputDevice::Gamepad),
+        }
+    }
+
+    // Whether any of this device's pad buttons went down this frame
+    fn pad_just_pressed(&self, device: InputDevice) -> bool {
+        self.pad_buttons
+            .get_just_pressed()
+            .any(|button| device.has_pad(button.gamepad))
+    }
+
+    // Whether any key or mouse button went down this frame
+    fn desktop_just_pressed(&self) -> bool {
+        self.keyboard.get_just_pressed().next().is_some()
+            || self.mouse.get_just_pressed().next().is_some()
+    }
+}
+
+// Everything one player asked for this frame. Gameplay reads this instead of
+// the keyboard, mouse and pads, so a replay can feed it back in.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerInput {
+    // Up to unit length, a stick pushed part way walks slower
+    pub movement: Vec2,
+    // Facing in radians, None keeps the current facing (cursor outside the window)
+    pub aim: Option<f32>,
+    // Aiming with a stick, so the aim gets pulled onto a nearby zombie
+    #[serde(default)]
+    pub aim_assist: bool,
+    pub fire: bool,
+    pub weapon: Option<WeaponType>,
+    // The rest are single presses
+    #[serde(default)]
+    pub cycle_weapon: bool,
+    pub interact: bool,
+    pub reload: bool,
+    pub buy_upgrade: bool,
+    pub buy_medkit: bool,
+    pub skip_intermission: bool,
+    pub pause: bool,
+}
+
+pub fn read_player_input(
+    actions: ActionInput,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut using_pad: Local<bool>,
+    windows: Query<&Window>,
+    camera_q: Query<(&Camera, &GlobalTransform)>,
+    mut players: Query<(&PlayerId, &Transform, &mut PlayerInput)>,
+) {
+    let player_count = players.iter().count();
+    let mouse_moved = mouse_motion.read().count() > 0;
+
+    for (id, transform, mut input) in &mut players {
+        let Some(device) = actions.device_for(id.0, player_count) else {
+            *input = PlayerInput::default();
+            continue;
+        };
+        let left_stick = actions.left_stick(device);
+        let right_stick = actions.right_stick(device);
+
+        // The cursor stays wherever it was left while playing on a pad, so
+        // only aim at it once the mouse or keyboard is picked up again
+        let mut cursor_aim_allowed = false;
+        if device.desktop() {
+            if left_stick.is_some() || right_stick.is_some() || actions.pad_just_pressed(device) {
+                *using_pad = true;
+            }
+            if mouse_moved || actions.desktop_just_pressed() {
+                *using_pad = false;
+            }
+            cursor_aim_allowed = !*using_pad;
+        }
+
+        let mut movement = Vec2::ZERO;
+        if actions.pressed_on(Action::MoveUp, device) {
+            movement.y += 1.0;
+        }
+        if actions.pressed_on(Action::MoveDown, device) {
+            movement.y -= 1.0;
+        }
+        if actions.pressed_on(Action::MoveLeft, device) {
+            movement.x -= 1.0;
+        }
+        if actions.pressed_on(Action::MoveRight, device) {
+            movement.x += 1.0;
+        }
+        let movement = left_stick.unwrap_or(movement.normalize_or_zero());
+
+        let aim = match right_stick {
+            Some(stick) => Some(stick.y.atan2(stick.x)),
+            None if cursor_aim_allowed => cursor_aim(&windows, &camera_q, transform),
+            None => None,
+        };
+
+        let weapon = if actions.just_pressed_on(Action::Pistol, device) {
+            Some(WeaponType::Pistol)
+        } else if actions.just_pressed_on(Action::Shotgun, device) {
+            Some(WeaponType::Shotgun)
+        } else if actions.just_pressed_on(Action::Rifle, device) {
+            Some(WeaponType::Rifle)
+        } else {
+            None
+        };
+
+        *input = PlayerInput {
+            movement,
+            aim,
+            aim_assist: right_stick.is_some(),
+            fire: actions.pressed_on(Action::Fire, device),
+            weapon,
+            cycle_weapon: actions.just_pressed_on(Action::NextWeapon, device),
+            interact: actions.just_pressed_on(Action::Interact, device),
+            reload: actions.just_pressed_on(Action::Reload, device),
+            buy_upgrade: actions.just_pressed_on(Action::BuyUpgrade, device),
+            buy_medkit: actions.just_pressed_on(Action::BuyMedkit, device),
+            skip_intermission: actions.just_pressed_on(Action::Confirm, device),
+            pause: actions.just_pressed_on(Action::Pause, device),
+        };
+    }
+}
+
+// Angle from the player to the mouse cursor in world space
+fn cursor_aim(
+    windows: &Query<&Window>,
+    camera_q: &Query<(&Camera, &GlobalTransform)>,
+    player_transform: &Transform,
+) -> Option<f32> {
+    let window = windows.get_single().ok()?;
+    let (camera, camera_transform) = camera_q.get_single().ok()?;
+
+    let world_position = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
+        .map(|ray| ray.origin.truncate())?;
+    let diff = world_position - player_transform.translation.truncate();
+    Some(diff.y.atan2(diff.x))
+}
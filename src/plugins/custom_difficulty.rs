@@ -0,0 +1,334 @@
+use crate::plugins::settings::{CustomDifficulty, Difficulty, GameSettings};
+use crate::plugins::ui::spawn_button;
+use crate::states::{DashboardScreen, GameState};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct CustomDifficultyPlugin;
+
+impl Plugin for CustomDifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        let mut presets: DifficultyPresets = crate::storage::load(PRESETS_SAVE).unwrap_or_default();
+        presets
+            .slots
+            .resize(PRESET_SLOTS, CustomDifficulty::STANDARD);
+        for slot in &mut presets.slots {
+            *slot = slot.clamped();
+        }
+
+        app.insert_resource(presets)
+            .add_systems(
+                OnEnter(DashboardScreen::CustomDifficulty),
+                setup_custom_difficulty,
+            )
+            .add_systems(
+                OnExit(DashboardScreen::CustomDifficulty),
+                cleanup_custom_difficulty,
+            )
+            .add_systems(
+                Update,
+                (
+                    custom_difficulty_interactions,
+                    custom_difficulty_visuals,
+                    update_field_text,
+                    update_preset_text,
+                )
+                    .run_if(in_state(DashboardScreen::CustomDifficulty)),
+            )
+            .add_systems(
+                Update,
+                save_presets.run_if(resource_changed::<DifficultyPresets>),
+            );
+    }
+}
+
+const PRESETS_SAVE: &str = "difficulty_presets";
+const PRESET_SLOTS: usize = 3;
+
+// Saved custom difficulties, one per slot
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DifficultyPresets {
+    pub slots: Vec<CustomDifficulty>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CustomField {
+    Waves,
+    ZombieHealth,
+    ZombieSpeed,
+    ZombieDamage,
+    SpawnRate,
+    Reward,
+    PlayerHealth,
+}
+
+impl CustomField {
+    const ALL: [CustomField; 7] = [
+        CustomField::Waves,
+        CustomField::ZombieHealth,
+        CustomField::ZombieSpeed,
+        CustomField::ZombieDamage,
+        CustomField::SpawnRate,
+        CustomField::Reward,
+        CustomField::PlayerHealth,
+    ];
+
+    fn label(&self, custom: &CustomDifficulty) -> String {
+        match self {
+            CustomField::Waves => format!("Waves: {}", custom.waves),
+            CustomField::ZombieHealth => format!("Zombie HP: x{:.1}", custom.zombie_health),
+            CustomField::ZombieSpeed => format!("Zombie Speed: x{:.1}", custom.zombie_speed),
+            CustomField::ZombieDamage => format!("Zombie Damage: x{:.1}", custom.zombie_damage),
+            CustomField::SpawnRate => format!("Spawn Rate: x{:.1}", custom.spawn_rate),
+            CustomField::Reward => format!("Rewards: x{:.1}", custom.reward),
+            CustomField::PlayerHealth => format!("Player HP: {:.0}", custom.player_health),
+        }
+    }
+
+    fn adjust(&self, custom: &mut CustomDifficulty, steps: i32) {
+        match self {
+            CustomField::Waves => {
+                custom.waves = custom
+                    .waves
+                    .saturating_add_signed(steps)
+                    .clamp(CustomDifficulty::MIN_WAVES, CustomDifficulty::MAX_WAVES);
+            }
+            CustomField::ZombieHealth => step_multiplier(&mut custom.zombie_health, steps),
+            CustomField::ZombieSpeed => step_multiplier(&mut custom.zombie_speed, steps),
+            CustomField::ZombieDamage => step_multiplier(&mut custom.zombie_damage, steps),
+            CustomField::SpawnRate => step_multiplier(&mut custom.spawn_rate, steps),
+            CustomField::Reward => step_multiplier(&mut custom.reward, steps),
+            CustomField::PlayerHealth => {
+                custom.player_health = (custom.player_health + 25.0 * steps as f32).clamp(
+                    CustomDifficulty::MIN_PLAYER_HEALTH,
+                    CustomDifficulty::MAX_PLAYER_HEALTH,
+                );
+            }
+        }
+    }
+}
+
+// 0.1 steps, rounded so repeated clicks don't drift
+fn step_multiplier(value: &mut f32, steps: i32) {
+    let min = CustomDifficulty::MIN_MULTIPLIER * 10.0;
+    let max = CustomDifficulty::MAX_MULTIPLIER * 10.0;
+    *value = ((*value * 10.0).round() + steps as f32).clamp(min, max) / 10.0;
+}
+
+fn preset_summary(slot: usize, custom: &CustomDifficulty) -> String {
+    format!(
+        "Preset {}: {} waves, HP x{:.1}, Speed x{:.1}, Dmg x{:.1}, Spawn x{:.1}, Reward x{:.1}, Player {:.0}",
+        slot + 1,
+        custom.waves,
+        custom.zombie_health,
+        custom.zombie_speed,
+        custom.zombie_damage,
+        custom.spawn_rate,
+        custom.reward,
+        custom.player_health
+    )
+}
+
+#[derive(Component)]
+struct CustomDifficultyUI;
+
+#[derive(Component)]
+struct FieldText(CustomField);
+
+#[derive(Component)]
+struct PresetText(usize);
+
+#[derive(Component)]
+enum CustomButton {
+    Adjust(CustomField, i32),
+    LoadPreset(usize),
+    SavePreset(usize),
+    Back,
+    Play,
+}
+
+fn setup_custom_difficulty(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    presets: Res<DifficultyPresets>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 1.0).into(), // Dark Grey Background
+                ..default()
+            },
+            CustomDifficultyUI,
+        ))
+        .with_children(|parent| {
+            // Title
+            parent.spawn(TextBundle::from_section(
+                "CUSTOM DIFFICULTY",
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::srgb(0.0, 1.0, 0.0), // Green Title
+                    ..default()
+                },
+            ));
+
+            // One row per knob: [-] value [+]
+            for field in CustomField::ALL {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(10.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        spawn_button(row, "-", 40.0, CustomButton::Adjust(field, -1));
+                        row.spawn((
+                            TextBundle::from_section(
+                                field.label(&settings.custom),
+                                TextStyle {
+                                    font_size: 24.0,
+                                    color: Color::WHITE,
+                                    ..default()
+                                },
+                            )
+                            .with_style(Style {
+                                width: Val::Px(260.0),
+                                ..default()
+                            }),
+                            FieldText(field),
+                        ));
+                        spawn_button(row, "+", 40.0, CustomButton::Adjust(field, 1));
+                    });
+            }
+
+            // Presets: [Load] [Save] summary
+            for (slot, preset) in presets.slots.iter().enumerate() {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(10.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        spawn_button(row, "Load", 70.0, CustomButton::LoadPreset(slot));
+                        spawn_button(row, "Save", 70.0, CustomButton::SavePreset(slot));
+                        row.spawn((
+                            TextBundle::from_section(
+                                preset_summary(slot, preset),
+                                TextStyle {
+                                    font_size: 16.0,
+                                    color: Color::srgb(0.8, 0.8, 0.8),
+                                    ..default()
+                                },
+                            ),
+                            PresetText(slot),
+                        ));
+                    });
+            }
+
+            // Back / Play
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(20.0),
+                        margin: UiRect::top(Val::Px(10.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    spawn_button(row, "Back", 200.0, CustomButton::Back);
+                    spawn_button(row, "PLAY", 200.0, CustomButton::Play);
+                });
+        });
+}
+
+fn cleanup_custom_difficulty(
+    mut commands: Commands,
+    query: Query<Entity, With<CustomDifficultyUI>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn custom_difficulty_interactions(
+    interaction_query: Query<(&Interaction, &CustomButton), (Changed<Interaction>, With<Button>)>,
+    mut settings: ResMut<GameSettings>,
+    mut presets: ResMut<DifficultyPresets>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_screen: ResMut<NextState<DashboardScreen>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            CustomButton::Adjust(field, steps) => field.adjust(&mut settings.custom, *steps),
+            CustomButton::LoadPreset(slot) => {
+                settings.custom = presets.slots[*slot];
+                info!("Loaded Preset {}", slot + 1);
+            }
+            CustomButton::SavePreset(slot) => {
+                presets.slots[*slot] = settings.custom;
+                info!("Saved Preset {}", slot + 1);
+            }
+            CustomButton::Back => next_screen.set(DashboardScreen::Main),
+            CustomButton::Play => {
+                settings.difficulty = Some(Difficulty::Custom);
+                next_state.set(GameState::Playing);
+            }
+        }
+    }
+}
+
+fn custom_difficulty_visuals(
+    mut query: Query<(&Interaction, &CustomButton, &mut BackgroundColor), With<Button>>,
+) {
+    for (interaction, button, mut color) in &mut query {
+        *color = match (interaction, button) {
+            (Interaction::Hovered, _) => Color::srgb(0.3, 0.3, 0.3).into(),
+            (_, CustomButton::Play) => Color::srgb(0.2, 0.6, 0.2).into(),
+            _ => Color::srgb(0.2, 0.2, 0.2).into(),
+        };
+    }
+}
+
+fn update_field_text(settings: Res<GameSettings>, mut query: Query<(&mut Text, &FieldText)>) {
+    for (mut text, field) in &mut query {
+        text.sections[0].value = field.0.label(&settings.custom);
+    }
+}
+
+fn update_preset_text(presets: Res<DifficultyPresets>, mut query: Query<(&mut Text, &PresetText)>) {
+    for (mut text, slot) in &mut query {
+        text.sections[0].value = preset_summary(slot.0, &presets.slots[slot.0]);
+    }
+}
+
+fn save_presets(presets: Res<DifficultyPresets>) {
+    // Skip the write triggered by inserting the resource at startup
+    if presets.is_added() {
+        return;
+    }
+    crate::storage::save(PRESETS_SAVE, &*presets);
+}
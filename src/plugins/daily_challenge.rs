@@ -0,0 +1,264 @@
+use crate::components::Door;
+use crate::plugins::game_mode::GameMode;
+use crate::plugins::replay::watching_replay;
+use crate::plugins::run_modifiers::Mutator;
+use crate::plugins::settings::{Difficulty, GameSettings};
+use crate::plugins::world::{UnlockedSections, open_section, section_name};
+use crate::states::{GameState, RunSetup, RunTeardown};
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub struct DailyChallengePlugin;
+
+impl Plugin for DailyChallengePlugin {
+    fn build(&self, app: &mut App) {
+        let leaderboard: DailyLeaderboard =
+            crate::storage::load(LEADERBOARD_SAVE).unwrap_or_default();
+
+        app.insert_resource(DailyChallenge::today())
+            .insert_resource(leaderboard)
+            .init_resource::<StashedGameMode>()
+            .add_systems(OnEnter(GameState::Dashboard), refresh_daily_challenge)
+            .add_systems(
+                RunSetup,
+                (
+                    open_daily_section,
+                    apply_daily_game_mode.before(crate::plugins::game_mode::setup_game_mode),
+                ),
+            )
+            // Before a finished replay hands back the settings it stashed
+            .add_systems(
+                RunTeardown,
+                restore_game_mode.before(crate::plugins::replay::stop_replay),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                record_daily_result.run_if(not(watching_replay)),
+            )
+            .add_systems(
+                OnEnter(GameState::Win),
+                record_daily_result.run_if(not(watching_replay)),
+            )
+            .add_systems(
+                Update,
+                save_leaderboard.run_if(resource_changed::<DailyLeaderboard>),
+            );
+    }
+}
+
+const LEADERBOARD_SAVE: &str = "daily_leaderboard";
+// Results kept per day
+const LEADERBOARD_SIZE: usize = 10;
+const MODIFIERS_PER_DAY: usize = 2;
+
+// Vampirism is left out, the daily run should never be easier than a normal one
+const DAILY_POOL: [Mutator; 8] = [
+    Mutator::ShotgunOnly,
+    Mutator::DoubleSpeedZombies,
+    Mutator::ToughZombies,
+    Mutator::NoShop,
+    Mutator::LowGravityBullets,
+    Mutator::GlassCannon,
+    Mutator::Fog,
+    Mutator::ExplosiveZombies,
+];
+
+// Today's run. Everything is derived from the date, so everyone playing on
+// the same (UTC) day gets the same difficulty, layout, modifiers and spawns.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct DailyChallenge {
+    // Days since 1970-01-01, enough to rebuild the whole challenge
+    pub day: u64,
+    // YYYY-MM-DD, also the leaderboard key
+    pub date: String,
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    // There is only one map, so the layout varies by which wing starts open
+    pub open_section: Option<u32>,
+    pub modifiers: Vec<Mutator>,
+}
+
+impl DailyChallenge {
+    pub fn today() -> Self {
+        let days = bevy::utils::SystemTime::now()
+            .duration_since(bevy::utils::SystemTime::UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_secs() / 86_400);
+        Self::for_day(days)
+    }
+
+    pub fn for_day(days_since_epoch: u64) -> Self {
+        let seed = crate::plugins::rng::splitmix64(days_since_epoch);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let difficulty = [Difficulty::Medium, Difficulty::Hard][rng.random_range(0..2)];
+        let open_section = [None, Some(1), Some(2)][rng.random_range(0..3)];
+        let mut modifiers = DAILY_POOL.to_vec();
+        modifiers.shuffle(&mut rng);
+        modifiers.truncate(MODIFIERS_PER_DAY);
+
+        Self {
+            day: days_since_epoch,
+            date: format_date(days_since_epoch),
+            seed,
+            difficulty,
+            open_section,
+            modifiers,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let modifiers: Vec<&str> = self.modifiers.iter().map(|m| m.label()).collect();
+        let layout = self
+            .open_section
+            .map(|section| format!(", {} open", section_name(section)))
+            .unwrap_or_default();
+        format!(
+            "{:?}{} | {}",
+            self.difficulty,
+            layout,
+            modifiers.join(" + ")
+        )
+    }
+}
+
+// Days since 1970-01-01 to a calendar date (Howard Hinnant's civil_from_days)
+fn format_date(days_since_epoch: u64) -> String {
+    let z = days_since_epoch as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub player: String,
+    pub won: bool,
+    pub wave: u32,
+    pub seconds: f32,
+    pub money: u32,
+}
+
+// Local results for each day's challenge, best first
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyLeaderboard {
+    pub days: BTreeMap<String, Vec<DailyResult>>,
+}
+
+impl DailyLeaderboard {
+    pub fn results(&self, date: &str) -> &[DailyResult] {
+        self.days.get(date).map_or(&[], Vec::as_slice)
+    }
+
+    fn record(&mut self, date: &str, result: DailyResult) {
+        let results = self.days.entry(date.to_string()).or_default();
+        results.push(result);
+        // Wins first, then furthest wave, then the faster win / longer survival
+        results.sort_by(|a, b| {
+            b.won.cmp(&a.won).then(b.wave.cmp(&a.wave)).then(if a.won {
+                a.seconds.total_cmp(&b.seconds)
+            } else {
+                b.seconds.total_cmp(&a.seconds)
+            })
+        });
+        results.truncate(LEADERBOARD_SIZE);
+    }
+}
+
+fn player_name() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "Player".to_string())
+}
+
+// A new day may have started while the game was open. Leaving the daily run
+// also drops its modifiers, so the next Play is a normal run again.
+fn refresh_daily_challenge(
+    mut challenge: ResMut<DailyChallenge>,
+    mut settings: ResMut<GameSettings>,
+) {
+    let today = DailyChallenge::today();
+    if *challenge != today {
+        *challenge = today;
+    }
+    settings.daily = None;
+}
+
+// The mode picked on the dashboard, put back once a daily run is over
+#[derive(Resource, Default)]
+pub struct StashedGameMode(Option<GameMode>);
+
+// The challenge always plays the waves out, whatever mode the dashboard is on
+pub fn apply_daily_game_mode(
+    mut settings: ResMut<GameSettings>,
+    mut stashed: ResMut<StashedGameMode>,
+) {
+    if settings.daily.is_none() {
+        return;
+    }
+    stashed.0 = Some(std::mem::replace(
+        &mut settings.game_mode,
+        GameMode::ClearWaves,
+    ));
+}
+
+fn restore_game_mode(mut settings: ResMut<GameSettings>, mut stashed: ResMut<StashedGameMode>) {
+    if let Some(game_mode) = stashed.0.take() {
+        settings.game_mode = game_mode;
+    }
+}
+
+fn open_daily_section(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    doors: Query<(Entity, &Door)>,
+    mut unlocked: ResMut<UnlockedSections>,
+) {
+    if let Some(section) = settings.daily.as_ref().and_then(|c| c.open_section) {
+        open_section(&mut commands, &doors, &mut unlocked, section);
+    }
+}
+
+fn record_daily_result(
+    state: Res<State<GameState>>,
+    settings: Res<GameSettings>,
+    wave_state: Res<crate::plugins::wave::WaveState>,
+    mode_state: Res<crate::plugins::game_mode::ModeState>,
+    wallet: Res<crate::plugins::shop::Wallet>,
+    mut leaderboard: ResMut<DailyLeaderboard>,
+) {
+    let Some(challenge) = &settings.daily else {
+        return;
+    };
+
+    let result = DailyResult {
+        player: player_name(),
+        won: *state.get() == GameState::Win,
+        wave: wave_state.current_wave,
+        seconds: mode_state.elapsed,
+        money: wallet.money,
+    };
+    info!(
+        "Daily {} result: wave {} in {:.0}s",
+        challenge.date, result.wave, result.seconds
+    );
+    leaderboard.record(&challenge.date, result);
+}
+
+fn save_leaderboard(leaderboard: Res<DailyLeaderboard>) {
+    // Skip the write triggered by inserting the resource at startup
+    if leaderboard.is_added() {
+        return;
+    }
+    crate::storage::save(LEADERBOARD_SAVE, &*leaderboard);
+}
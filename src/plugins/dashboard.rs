@@ -1,24 +1,30 @@
-use crate::states::GameState;
+use crate::states::{DashboardScreen, GameState, RunTeardown};
 use bevy::prelude::*;
 
 pub struct DashboardPlugin;
 
 impl Plugin for DashboardPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            OnEnter(GameState::Dashboard),
-            (setup_dashboard, cleanup_level),
-        )
-        .add_systems(OnExit(GameState::Dashboard), cleanup_dashboard)
-        .add_systems(
-            Update,
-            (
-                dashboard_interactions,
-                dashboard_visuals,
-                update_dashboard_stats,
-            )
-                .run_if(in_state(GameState::Dashboard)),
-        );
+        app.init_resource::<SeedEntry>()
+            .add_systems(RunTeardown, cleanup_level)
+            .add_systems(OnEnter(DashboardScreen::Main), setup_dashboard)
+            .add_systems(OnExit(DashboardScreen::Main), cleanup_dashboard)
+            .add_systems(
+                Update,
+                (
+                    dashboard_interactions,
+                    dashboard_visuals,
+                    update_dashboard_stats,
+                    update_effects_button,
+                    update_mode_button,
+                    update_players_button,
+                    update_daily_text,
+                    update_mutator_text,
+                    seed_typing,
+                    update_seed_button,
+                )
+                    .run_if(in_state(DashboardScreen::Main)),
+            );
     }
 }
 
@@ -27,15 +33,19 @@ fn update_dashboard_stats(
     stats: Res<crate::plugins::progression::GlobalPlayerStats>,
 ) {
     for mut text in &mut query {
-        text.sections[0].value = format!("Level: {} | Money: ${}", stats.level, stats.total_money);
+        text.sections[0].value = format!(
+            "Level: {} | Money: ${} | Best Endless Wave: {}",
+            stats.level, stats.total_money, stats.best_endless_wave
+        );
     }
 }
 
-fn cleanup_level(
+pub fn cleanup_level(
     mut commands: Commands,
     players: Query<Entity, With<crate::plugins::player::Player>>,
     zombies: Query<Entity, With<crate::components::Zombie>>,
     projectiles: Query<Entity, With<crate::components::Projectile>>,
+    telegraphs: Query<Entity, With<crate::plugins::spawning::SpawnTelegraph>>,
 ) {
     for entity in &players {
         commands.entity(entity).despawn_recursive();
@@ -46,11 +56,171 @@ fn cleanup_level(
     for entity in &projectiles {
         commands.entity(entity).despawn_recursive();
     }
+    for entity in &telegraphs {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn update_effects_button(
+    mut query: Query<&mut Text, With<EffectsButtonText>>,
+    settings: Res<crate::plugins::settings::GameSettings>,
+) {
+    for mut text in &mut query {
+        text.sections[0].value = format!("Effects: {}", settings.camera_effects.label());
+    }
+}
+
+fn update_mode_button(
+    mut query: Query<&mut Text, With<ModeButtonText>>,
+    settings: Res<crate::plugins::settings::GameSettings>,
+) {
+    for mut text in &mut query {
+        text.sections[0].value = format!("Mode: {}", settings.game_mode.label());
+    }
+}
+
+fn update_players_button(
+    mut query: Query<&mut Text, With<PlayersButtonText>>,
+    settings: Res<crate::plugins::settings::GameSettings>,
+) {
+    for mut text in &mut query {
+        text.sections[0].value = format!("Players: {}", settings.player_count());
+    }
+}
+
+fn daily_summary(
+    challenge: &crate::plugins::daily_challenge::DailyChallenge,
+    leaderboard: &crate::plugins::daily_challenge::DailyLeaderboard,
+) -> String {
+    let mut summary = format!("{}: {}", challenge.date, challenge.describe());
+    for (rank, result) in leaderboard
+        .results(&challenge.date)
+        .iter()
+        .take(3)
+        .enumerate()
+    {
+        summary.push_str(&format!(
+            "\n{}. {} - {} wave {} ({:.0}s)",
+            rank + 1,
+            result.player,
+            if result.won { "Won," } else { "Died on" },
+            result.wave,
+            result.seconds
+        ));
+    }
+    summary
+}
+
+fn update_daily_text(
+    mut query: Query<&mut Text, With<DailyText>>,
+    challenge: Res<crate::plugins::daily_challenge::DailyChallenge>,
+    leaderboard: Res<crate::plugins::daily_challenge::DailyLeaderboard>,
+) {
+    for mut text in &mut query {
+        text.sections[0].value = daily_summary(&challenge, &leaderboard);
+    }
+}
+
+fn mutator_summary(modifiers: &crate::plugins::run_modifiers::RunModifiers) -> String {
+    format!(
+        "Mutators: Rewards x{:.2}",
+        crate::plugins::run_modifiers::RunModifiers::reward_multiplier(&modifiers.selected)
+    )
+}
+
+fn update_mutator_text(
+    mut query: Query<&mut Text, With<MutatorText>>,
+    modifiers: Res<crate::plugins::run_modifiers::RunModifiers>,
+) {
+    for mut text in &mut query {
+        text.sections[0].value = mutator_summary(&modifiers);
+    }
+}
+
+// The seed field, typed into after clicking it
+#[derive(Resource, Default)]
+struct SeedEntry {
+    editing: bool,
+    text: String,
+}
+
+fn seed_label(entry: &SeedEntry, seed: Option<u64>) -> String {
+    if entry.editing {
+        format!("Seed: {}_", entry.text)
+    } else {
+        match seed {
+            Some(seed) => format!("Seed: {}", seed),
+            None => "Seed: Random".to_string(),
+        }
+    }
+}
+
+fn seed_typing(
+    mut keyboard_events: EventReader<bevy::input::keyboard::KeyboardInput>,
+    mut entry: ResMut<SeedEntry>,
+    mut settings: ResMut<crate::plugins::settings::GameSettings>,
+) {
+    if !entry.editing {
+        keyboard_events.clear();
+        return;
+    }
+
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        match &event.logical_key {
+            bevy::input::keyboard::Key::Character(c) if c.chars().all(|c| c.is_ascii_digit()) => {
+                let text = format!("{}{}", entry.text, c);
+                // Ignore digits that would overflow a u64
+                if text.parse::<u64>().is_ok() {
+                    entry.text = text;
+                }
+            }
+            bevy::input::keyboard::Key::Backspace => {
+                entry.text.pop();
+            }
+            bevy::input::keyboard::Key::Enter => {
+                entry.editing = false;
+                settings.seed = entry.text.parse().ok();
+                info!("Seed: {:?}", settings.seed);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn update_seed_button(
+    mut query: Query<&mut Text, With<SeedButtonText>>,
+    entry: Res<SeedEntry>,
+    settings: Res<crate::plugins::settings::GameSettings>,
+) {
+    for mut text in &mut query {
+        text.sections[0].value = seed_label(&entry, settings.seed);
+    }
 }
 
 #[derive(Component)]
 struct DashboardUI;
 
+#[derive(Component)]
+struct SeedButtonText;
+
+#[derive(Component)]
+struct MutatorText;
+
+#[derive(Component)]
+struct DailyText;
+
+#[derive(Component)]
+struct ModeButtonText;
+
+#[derive(Component)]
+struct PlayersButtonText;
+
+#[derive(Component)]
+struct EffectsButtonText;
+
 #[derive(Component)]
 struct DashboardStatsText;
 
@@ -64,12 +234,28 @@ enum DashboardButton {
     DifficultyEasy,
     DifficultyMed,
     DifficultyHard,
+    DifficultyEndless,
+    DifficultyCustom,
+    DailyChallenge,
+    ToggleMutator(crate::plugins::run_modifiers::Mutator),
+    ToggleEffects,
+    CycleMode,
+    CyclePlayers,
+    EditSeed,
+    Replays,
+    Controls,
+    Online,
     Quit,
 }
 
 fn setup_dashboard(
     mut commands: Commands,
     stats: Res<crate::plugins::progression::GlobalPlayerStats>,
+    settings: Res<crate::plugins::settings::GameSettings>,
+    challenge: Res<crate::plugins::daily_challenge::DailyChallenge>,
+    leaderboard: Res<crate::plugins::daily_challenge::DailyLeaderboard>,
+    modifiers: Res<crate::plugins::run_modifiers::RunModifiers>,
+    seed_entry: Res<SeedEntry>,
 ) {
     commands
         .spawn((
@@ -80,7 +266,7 @@ fn setup_dashboard(
                     justify_content: JustifyContent::Center,
                     align_items: AlignItems::Center,
                     flex_direction: FlexDirection::Column,
-                    row_gap: Val::Px(20.0),
+                    row_gap: Val::Px(12.0),
                     ..default()
                 },
                 background_color: Color::srgba(0.1, 0.1, 0.1, 1.0).into(), // Dark Grey Background
@@ -104,7 +290,10 @@ fn setup_dashboard(
             // Global Stats Display
             parent.spawn((
                 TextBundle::from_section(
-                    format!("Level: {} | Money: ${}", stats.level, stats.total_money),
+                    format!(
+                        "Level: {} | Money: ${} | Best Endless Wave: {}",
+                        stats.level, stats.total_money, stats.best_endless_wave
+                    ),
                     TextStyle {
                         font_size: 30.0,
                         color: Color::WHITE,
@@ -211,6 +400,335 @@ fn setup_dashboard(
                                 },
                             ));
                         });
+
+                    // Endless
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(100.0),
+                                    height: Val::Px(40.0),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: Color::srgb(0.4, 0.1, 0.4).into(),
+                                ..default()
+                            },
+                            DashboardButton::DifficultyEndless,
+                        ))
+                        .with_children(|p| {
+                            p.spawn(TextBundle::from_section(
+                                "Endless",
+                                TextStyle {
+                                    font_size: 20.0,
+                                    ..default()
+                                },
+                            ));
+                        });
+
+                    // Custom (opens the difficulty builder)
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(100.0),
+                                    height: Val::Px(40.0),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: Color::srgb(0.1, 0.4, 0.5).into(),
+                                ..default()
+                            },
+                            DashboardButton::DifficultyCustom,
+                        ))
+                        .with_children(|p| {
+                            p.spawn(TextBundle::from_section(
+                                "Custom...",
+                                TextStyle {
+                                    font_size: 20.0,
+                                    ..default()
+                                },
+                            ));
+                        });
+                });
+
+            // Daily Challenge (same seeded run for everyone today)
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(160.0),
+                                    height: Val::Px(40.0),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: Color::srgb(0.6, 0.4, 0.0).into(),
+                                ..default()
+                            },
+                            DashboardButton::DailyChallenge,
+                        ))
+                        .with_children(|p| {
+                            p.spawn(TextBundle::from_section(
+                                "Daily Challenge",
+                                TextStyle {
+                                    font_size: 20.0,
+                                    ..default()
+                                },
+                            ));
+                        });
+
+                    parent.spawn((
+                        TextBundle::from_section(
+                            daily_summary(&challenge, &leaderboard),
+                            TextStyle {
+                                font_size: 16.0,
+                                color: Color::srgb(0.8, 0.8, 0.8),
+                                ..default()
+                            },
+                        ),
+                        DailyText,
+                    ));
+                });
+
+            // Mutators (harder combinations pay out more)
+            parent.spawn((
+                TextBundle::from_section(
+                    mutator_summary(&modifiers),
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                MutatorText,
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        max_width: Val::Px(900.0),
+                        column_gap: Val::Px(8.0),
+                        row_gap: Val::Px(8.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for mutator in crate::plugins::run_modifiers::Mutator::ALL {
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        padding: UiRect::axes(Val::Px(10.0), Val::Px(6.0)),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                                    ..default()
+                                },
+                                DashboardButton::ToggleMutator(mutator),
+                            ))
+                            .with_children(|p| {
+                                p.spawn(TextBundle::from_section(
+                                    mutator.label(),
+                                    TextStyle {
+                                        font_size: 16.0,
+                                        ..default()
+                                    },
+                                ));
+                            });
+                    }
+                });
+
+            // Effects, game mode, players and seed share a row
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    // Camera Effects Toggle (screen shake, hit-stop, zoom)
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(200.0),
+                                height: Val::Px(40.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                            ..default()
+                        },
+                        DashboardButton::ToggleEffects,
+                    ))
+                    .with_children(|p| {
+                        p.spawn((
+                            TextBundle::from_section(
+                                format!("Effects: {}", settings.camera_effects.label()),
+                                TextStyle {
+                                    font_size: 20.0,
+                                    ..default()
+                                },
+                            ),
+                            EffectsButtonText,
+                        ));
+                    });
+
+                    // Game Mode Toggle (how the run is won)
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(200.0),
+                                height: Val::Px(40.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                            ..default()
+                        },
+                        DashboardButton::CycleMode,
+                    ))
+                    .with_children(|p| {
+                        p.spawn((
+                            TextBundle::from_section(
+                                format!("Mode: {}", settings.game_mode.label()),
+                                TextStyle {
+                                    font_size: 20.0,
+                                    ..default()
+                                },
+                            ),
+                            ModeButtonText,
+                        ));
+                    });
+
+                    // Local co-op, player 1 on keyboard & mouse and the rest on pads
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(140.0),
+                                height: Val::Px(40.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                            ..default()
+                        },
+                        DashboardButton::CyclePlayers,
+                    ))
+                    .with_children(|p| {
+                        p.spawn((
+                            TextBundle::from_section(
+                                format!("Players: {}", settings.player_count()),
+                                TextStyle {
+                                    font_size: 20.0,
+                                    ..default()
+                                },
+                            ),
+                            PlayersButtonText,
+                        ));
+                    });
+
+                    // Seed (type one in to replay a run, empty for random)
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(260.0),
+                                height: Val::Px(40.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                            ..default()
+                        },
+                        DashboardButton::EditSeed,
+                    ))
+                    .with_children(|p| {
+                        p.spawn((
+                            TextBundle::from_section(
+                                seed_label(&seed_entry, settings.seed),
+                                TextStyle {
+                                    font_size: 20.0,
+                                    ..default()
+                                },
+                            ),
+                            SeedButtonText,
+                        ));
+                    });
+
+                    // Replays (watch a saved run again)
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(160.0),
+                                height: Val::Px(40.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                            ..default()
+                        },
+                        DashboardButton::Replays,
+                    ))
+                    .with_children(|p| {
+                        p.spawn(TextBundle::from_section(
+                            "Replays",
+                            TextStyle {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                        ));
+                    });
+
+                    // Controls (rebind keys)
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(160.0),
+                                height: Val::Px(40.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                            ..default()
+                        },
+                        DashboardButton::Controls,
+                    ))
+                    .with_children(|p| {
+                        p.spawn(TextBundle::from_section(
+                            "Controls",
+                            TextStyle {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                        ));
+                    });
                 });
 
             // Play Button
@@ -240,6 +758,32 @@ fn setup_dashboard(
                     ));
                 });
 
+            // Online co-op (host or join a lobby)
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(200.0),
+                            height: Val::Px(40.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                        ..default()
+                    },
+                    DashboardButton::Online,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Online Co-op",
+                        TextStyle {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                    ));
+                });
+
             // Shop Section
             parent.spawn(TextBundle::from_section(
                 "SHOP",
@@ -434,9 +978,13 @@ fn dashboard_interactions(
         (Changed<Interaction>, With<Button>),
     >,
     mut next_state: ResMut<NextState<GameState>>,
+    mut next_screen: ResMut<NextState<DashboardScreen>>,
     mut exit: EventWriter<AppExit>,
     mut global_stats: ResMut<crate::plugins::progression::GlobalPlayerStats>,
     mut settings: ResMut<crate::plugins::settings::GameSettings>,
+    challenge: Res<crate::plugins::daily_challenge::DailyChallenge>,
+    mut modifiers: ResMut<crate::plugins::run_modifiers::RunModifiers>,
+    mut seed_entry: ResMut<SeedEntry>,
 ) {
     for (interaction, button) in &mut interaction_query {
         if *interaction == Interaction::Pressed {
@@ -462,6 +1010,62 @@ fn dashboard_interactions(
                     settings.difficulty = Some(crate::plugins::settings::Difficulty::Hard);
                     info!("Difficulty: Hard");
                 }
+                DashboardButton::DifficultyEndless => {
+                    settings.difficulty = Some(crate::plugins::settings::Difficulty::Endless);
+                    info!("Difficulty: Endless");
+                }
+                DashboardButton::DifficultyCustom => {
+                    settings.difficulty = Some(crate::plugins::settings::Difficulty::Custom);
+                    next_screen.set(DashboardScreen::CustomDifficulty);
+                }
+                DashboardButton::DailyChallenge => {
+                    // The challenge decides the difficulty, the mode is only
+                    // overridden for the run itself
+                    settings.difficulty = Some(challenge.difficulty);
+                    settings.daily = Some(challenge.clone());
+                    info!(
+                        "Daily Challenge {}: {}",
+                        challenge.date,
+                        challenge.describe()
+                    );
+                    next_state.set(GameState::Playing);
+                }
+
+                DashboardButton::ToggleMutator(mutator) => {
+                    modifiers.toggle(*mutator);
+                    info!("Mutators: {:?}", modifiers.selected);
+                }
+
+                DashboardButton::EditSeed => {
+                    // Clicking again confirms, like pressing ENTER
+                    if seed_entry.editing {
+                        seed_entry.editing = false;
+                        settings.seed = seed_entry.text.parse().ok();
+                        info!("Seed: {:?}", settings.seed);
+                    } else {
+                        seed_entry.editing = true;
+                        seed_entry.text = settings
+                            .seed
+                            .map(|seed| seed.to_string())
+                            .unwrap_or_default();
+                    }
+                }
+                DashboardButton::ToggleEffects => {
+                    settings.camera_effects = settings.camera_effects.next_preset();
+                    info!("Camera Effects: {}", settings.camera_effects.label());
+                }
+                DashboardButton::CycleMode => {
+                    settings.game_mode = settings.game_mode.next();
+                    info!("Game Mode: {}", settings.game_mode.label());
+                }
+                DashboardButton::CyclePlayers => {
+                    settings.extra_players =
+                        (settings.extra_players + 1) % crate::plugins::player::MAX_PLAYERS;
+                    info!("Players: {}", settings.player_count());
+                }
+                DashboardButton::Replays => next_screen.set(DashboardScreen::Replays),
+                DashboardButton::Controls => next_screen.set(DashboardScreen::Controls),
+                DashboardButton::Online => next_state.set(GameState::Lobby),
 
                 // Shop
                 DashboardButton::UpgradeWeapon => {
@@ -504,7 +1108,8 @@ fn dashboard_interactions(
 fn dashboard_visuals(
     mut query: Query<(&Interaction, &DashboardButton, &mut BackgroundColor), With<Button>>,
     settings: Res<crate::plugins::settings::GameSettings>,
-    // Add other resources if needed for visual states (like unlocked buttons) but currently only difficulty is stateful
+    modifiers: Res<crate::plugins::run_modifiers::RunModifiers>,
+    // Add other resources if needed for visual states (like unlocked buttons)
 ) {
     for (interaction, button, mut color) in &mut query {
         if *interaction == Interaction::Hovered {
@@ -534,6 +1139,28 @@ fn dashboard_visuals(
                     *color = Color::srgb(0.6, 0.2, 0.2).into(); // Red
                 }
             }
+            DashboardButton::DifficultyEndless => {
+                if settings.difficulty == Some(crate::plugins::settings::Difficulty::Endless) {
+                    *color = Color::srgb(0.5, 0.5, 0.5).into(); // Gray (Selected)
+                } else {
+                    *color = Color::srgb(0.4, 0.1, 0.4).into(); // Purple
+                }
+            }
+            DashboardButton::DifficultyCustom => {
+                if settings.difficulty == Some(crate::plugins::settings::Difficulty::Custom) {
+                    *color = Color::srgb(0.5, 0.5, 0.5).into(); // Gray (Selected)
+                } else {
+                    *color = Color::srgb(0.1, 0.4, 0.5).into(); // Teal
+                }
+            }
+            DashboardButton::DailyChallenge => *color = Color::srgb(0.6, 0.4, 0.0).into(), // Orange
+            DashboardButton::ToggleMutator(mutator) => {
+                if modifiers.selected.contains(mutator) {
+                    *color = Color::srgb(0.6, 0.2, 0.2).into(); // Red (On)
+                } else {
+                    *color = Color::srgb(0.2, 0.2, 0.2).into(); // Off
+                }
+            }
             DashboardButton::Play => {
                 if settings.difficulty.is_none() {
                     *color = Color::srgb(0.1, 0.1, 0.1).into(); // Disabled look
@@ -0,0 +1,62 @@
+use crate::components::Door;
+use crate::plugins::player::Player;
+use crate::plugins::shop::Wallet;
+use crate::plugins::world::UnlockedSections;
+use bevy::prelude::*;
+
+pub struct DoorsPlugin;
+
+impl Plugin for DoorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            open_doors.run_if(in_state(crate::states::GameState::Playing)),
+        );
+    }
+}
+
+// How close the player has to stand to buy a door
+const DOOR_INTERACT_RANGE: f32 = 50.0;
+
+fn open_doors(
+    mut commands: Commands,
+    player_query: Query<
+        (&Transform, &crate::plugins::controls::PlayerInput),
+        (With<Player>, Without<crate::plugins::player::Downed>),
+    >,
+    door_query: Query<(Entity, &Transform, &Door)>,
+    mut wallet: ResMut<Wallet>,
+    mut unlocked: ResMut<UnlockedSections>,
+) {
+    // Whoever pressed interact buys the door next to them
+    let Some((player_transform, _)) = player_query.iter().find(|(_, input)| input.interact) else {
+        return;
+    };
+
+    // Pick the closest door in range
+    let nearest = door_query
+        .iter()
+        .map(|(entity, transform, door)| {
+            let distance = player_transform
+                .translation
+                .truncate()
+                .distance(transform.translation.truncate());
+            (entity, door, distance)
+        })
+        .filter(|(_, _, distance)| *distance < DOOR_INTERACT_RANGE)
+        .min_by(|a, b| a.2.total_cmp(&b.2));
+
+    if let Some((entity, door, _)) = nearest {
+        if wallet.money >= door.cost {
+            wallet.money -= door.cost;
+            unlocked.0.insert(door.section);
+            commands.entity(entity).despawn_recursive();
+            info!(
+                "Door Opened! Section {} unlocked. Current Money: {}",
+                door.section, wallet.money
+            );
+        } else {
+            info!("Not enough money! Door costs ${}", door.cost);
+        }
+    }
+}
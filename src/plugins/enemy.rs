@@ -1,73 +1,215 @@
 use bevy::prelude::*;
+use serde::Deserialize;
 use crate::components::Zombie;
-use crate::plugins::player::Player;
+use crate::plugins::run_modifiers::{Mutator, mutator_active};
+use crate::plugins::simulation::{GameplaySet, Interpolated};
 
 pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_initial_zombies)
-           .add_systems(Update, zombie_chase.run_if(in_state(crate::states::GameState::Playing)));
+        app.add_systems(
+               FixedUpdate,
+               zombie_chase
+                   .in_set(GameplaySet::Ai)
+                   .run_if(in_state(crate::states::GameState::Playing)),
+           )
+           .add_systems(
+               Update,
+               fog_of_war
+                   .run_if(in_state(crate::states::GameState::Playing))
+                   .run_if(mutator_active(Mutator::Fog)),
+           );
     }
 }
 
-fn spawn_initial_zombies(mut commands: Commands) {
-    // Spawn a few testing zombies
-    let positions = vec![
-        Vec3::new(200.0, 200.0, 0.5),
-        Vec3::new(-200.0, 200.0, 0.5),
-        Vec3::new(200.0, -200.0, 0.5),
-        Vec3::new(-200.0, -200.0, 0.5),
-    ];
-
-    for pos in positions {
-        commands.spawn((
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ZombieArchetype {
+    Walker,
+    Runner,
+    Brute,
+    Tank,
+    Boss,
+}
+
+pub struct ZombieStats {
+    pub size: f32,
+    pub hp: f32,
+    pub speed: f32,
+    pub reward: u32,
+    pub color: Color,
+}
+
+impl ZombieArchetype {
+    pub fn stats(&self) -> ZombieStats {
+        match self {
+            // Level 1: Small, easy to kill
+            ZombieArchetype::Walker => ZombieStats {
+                size: 10.0,
+                hp: 10.0,
+                speed: 80.0,
+                reward: 10,
+                color: Color::srgb(1.0, 0.0, 0.0),
+            },
+            // Fast but fragile
+            ZombieArchetype::Runner => ZombieStats {
+                size: 8.0,
+                hp: 6.0,
+                speed: 140.0,
+                reward: 15,
+                color: Color::srgb(1.0, 0.5, 0.0),
+            },
+            // Level 2: Medium size, tougher
+            ZombieArchetype::Brute => ZombieStats {
+                size: 20.0,
+                hp: 20.0,
+                speed: 70.0,
+                reward: 20,
+                color: Color::srgb(0.8, 0.0, 0.0),
+            },
+            // Level 3: Large, high HP
+            ZombieArchetype::Tank => ZombieStats {
+                size: 30.0,
+                hp: 40.0,
+                speed: 55.0,
+                reward: 30,
+                color: Color::srgb(0.5, 0.0, 0.0),
+            },
+            ZombieArchetype::Boss => ZombieStats {
+                size: 50.0,
+                hp: 300.0,
+                speed: 50.0,
+                reward: 150,
+                color: Color::srgb(0.5, 0.0, 0.5),
+            },
+        }
+    }
+}
+
+// Contact damage per second against the player
+const ZOMBIE_DPS: f32 = 10.0;
+
+// Per-wave multipliers on top of the archetype stats
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZombieScaling {
+    pub health: f32,
+    pub speed: f32,
+    pub damage: f32,
+    pub reward: f32,
+}
+
+impl Default for ZombieScaling {
+    fn default() -> Self {
+        Self {
+            health: 1.0,
+            speed: 1.0,
+            damage: 1.0,
+            reward: 1.0,
+        }
+    }
+}
+
+pub fn spawn_zombie(
+    commands: &mut Commands,
+    archetype: ZombieArchetype,
+    position: Vec2,
+    scaling: ZombieScaling,
+) -> Entity {
+    let stats = archetype.stats();
+    let hp = stats.hp * scaling.health;
+
+    commands
+        .spawn((
             SpriteBundle {
                 sprite: Sprite {
-                    color: Color::srgb(1.0, 0.0, 0.0), // Red Zombie
-                    custom_size: Some(Vec2::new(10.0, 10.0)),
+                    color: stats.color,
+                    custom_size: Some(Vec2::splat(stats.size)),
                     ..default()
                 },
-                transform: Transform::from_translation(pos),
+                transform: Transform::from_translation(position.extend(0.5)),
                 ..default()
             },
-            Zombie { money_reward: 10 },
-            crate::components::Health { current: 10.0, max: 10.0 },
-        ));
-    }
+            Zombie {
+                money_reward: (stats.reward as f32 * scaling.reward).round() as u32,
+                speed: stats.speed * scaling.speed,
+                damage: ZOMBIE_DPS * scaling.damage,
+            },
+            crate::components::Health {
+                current: hp,
+                max: hp,
+            },
+            Interpolated::at(position),
+        ))
+        .id()
 }
 
 fn zombie_chase(
-    player_query: Query<&Transform, With<Player>>,
-    mut zombie_query: Query<(&mut Transform, &Sprite), (With<Zombie>, Without<Player>)>,
+    target_query: Query<&Transform, (With<crate::components::ZombieTarget>, Without<Zombie>)>,
+    mut zombie_query: Query<(&mut Transform, &Sprite, &Zombie)>,
     wall_query: Query<(&Transform, &crate::components::Collider), (With<crate::components::Wall>, Without<Zombie>)>,
     time: Res<Time>,
 ) {
-    let speed = 80.0;
-    if let Ok(player_transform) = player_query.get_single() {
-        for (mut zombie_transform, sprite) in &mut zombie_query {
-            let direction = (player_transform.translation - zombie_transform.translation).normalize_or_zero();
-            let proposed = zombie_transform.translation + direction * speed * time.delta_seconds();
-            
-            let zombie_size = sprite.custom_size.unwrap_or(Vec2::new(32.0, 32.0));
-            let mut collision = false;
-            
-            for (w_transform, w_collider) in &wall_query {
-                 let z_min = proposed.truncate() - zombie_size / 2.0;
-                 let z_max = proposed.truncate() + zombie_size / 2.0;
-                 let w_min = w_transform.translation.truncate() - w_collider.size / 2.0;
-                 let w_max = w_transform.translation.truncate() + w_collider.size / 2.0;
-
-                 if z_min.x < w_max.x && z_max.x > w_min.x &&
-                    z_min.y < w_max.y && z_max.y > w_min.y {
-                     collision = true;
-                     break;
-                 }
-            }
-
-            if !collision {
-                zombie_transform.translation = proposed;
-            }
+    for (mut zombie_transform, sprite, zombie) in &mut zombie_query {
+        // Go for whichever target is closest (the player or an objective)
+        let Some(target) = target_query
+            .iter()
+            .map(|transform| transform.translation)
+            .min_by(|a, b| {
+                a.distance_squared(zombie_transform.translation)
+                    .total_cmp(&b.distance_squared(zombie_transform.translation))
+            })
+        else {
+            return;
+        };
+
+        let direction = (target - zombie_transform.translation).normalize_or_zero();
+        let proposed = zombie_transform.translation + direction * zombie.speed * time.delta_seconds();
+
+        let zombie_size = sprite.custom_size.unwrap_or(Vec2::new(32.0, 32.0));
+        let mut collision = false;
+
+        for (w_transform, w_collider) in &wall_query {
+             let z_min = proposed.truncate() - zombie_size / 2.0;
+             let z_max = proposed.truncate() + zombie_size / 2.0;
+             let w_min = w_transform.translation.truncate() - w_collider.size / 2.0;
+             let w_max = w_transform.translation.truncate() + w_collider.size / 2.0;
+
+             if z_min.x < w_max.x && z_max.x > w_min.x &&
+                z_min.y < w_max.y && z_max.y > w_min.y {
+                 collision = true;
+                 break;
+             }
         }
+
+        if !collision {
+            zombie_transform.translation = proposed;
+        }
+    }
+}
+
+// Zombies are fully visible up close and fade out completely by FOG_FAR
+const FOG_NEAR: f32 = 150.0;
+const FOG_FAR: f32 = 280.0;
+
+fn fog_of_war(
+    player_query: Query<&Transform, With<crate::plugins::player::Player>>,
+    mut zombie_query: Query<(&Transform, &mut Sprite), With<Zombie>>,
+) {
+    let players: Vec<Vec2> = player_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    if players.is_empty() {
+        return;
+    }
+
+    // Whoever is closest sees the zombie
+    for (z_transform, mut sprite) in &mut zombie_query {
+        let distance = players
+            .iter()
+            .map(|player| player.distance(z_transform.translation.truncate()))
+            .fold(f32::MAX, f32::min);
+        let visibility = 1.0 - ((distance - FOG_NEAR) / (FOG_FAR - FOG_NEAR)).clamp(0.0, 1.0);
+        sprite.color.set_alpha(visibility);
     }
 }
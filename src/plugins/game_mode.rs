@@ -0,0 +1,315 @@
+use crate::components::{Collider, Health, Wall, Zombie, ZombieTarget};
+use crate::plugins::player::{Downed, Player};
+use crate::plugins::simulation::GameplaySet;
+use crate::states::{GameState, RunSetup, RunTeardown};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct GameModePlugin;
+
+impl Plugin for GameModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ModeState>()
+            .init_resource::<Objective>()
+            .add_event::<AllWavesCleared>()
+            .add_systems(RunSetup, setup_game_mode)
+            .add_systems(RunTeardown, cleanup_game_mode)
+            .add_systems(
+                FixedUpdate,
+                (
+                    tick_mode_clock,
+                    player_death_rule,
+                    clear_waves_rule.run_if(game_mode_is(GameMode::ClearWaves)),
+                    survival_rule.run_if(game_mode_is(GameMode::Survival)),
+                    defend_generator_rule.run_if(game_mode_is(GameMode::DefendGenerator)),
+                    extraction_rule.run_if(game_mode_is(GameMode::Extraction)),
+                )
+                    .chain()
+                    .in_set(GameplaySet::Rules)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+// Win/loss rules for a run. Waves keep coming in every mode except ClearWaves,
+// where running out of scripted waves is the win condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    ClearWaves,
+    Survival,
+    DefendGenerator,
+    Extraction,
+}
+
+impl GameMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameMode::ClearWaves => "Clear Waves",
+            GameMode::Survival => "Survival",
+            GameMode::DefendGenerator => "Defend",
+            GameMode::Extraction => "Extraction",
+        }
+    }
+
+    // Dashboard toggle cycles through the modes
+    pub fn next(&self) -> Self {
+        match self {
+            GameMode::ClearWaves => GameMode::Survival,
+            GameMode::Survival => GameMode::DefendGenerator,
+            GameMode::DefendGenerator => GameMode::Extraction,
+            GameMode::Extraction => GameMode::ClearWaves,
+        }
+    }
+
+    pub fn ends_after_last_wave(&self) -> bool {
+        *self == GameMode::ClearWaves
+    }
+}
+
+pub fn game_mode_is(
+    mode: GameMode,
+) -> impl Fn(Res<crate::plugins::settings::GameSettings>) -> bool {
+    move |settings| settings.game_mode == mode
+}
+
+// Sent by the wave manager when a scripted run has no waves left
+#[derive(Event)]
+pub struct AllWavesCleared;
+
+// HUD line describing what the player has to do right now
+#[derive(Resource, Default)]
+pub struct Objective {
+    pub text: String,
+}
+
+#[derive(Resource, Default)]
+pub struct ModeState {
+    // Seconds spent in Playing this run (pauses don't count)
+    pub elapsed: f32,
+    pub extraction_hold: f32,
+}
+
+const SURVIVAL_SECONDS: f32 = 180.0;
+const DEFEND_SECONDS: f32 = 150.0;
+const GENERATOR_HEALTH: f32 = 500.0;
+const GENERATOR_SIZE: f32 = 40.0;
+const GENERATOR_POSITION: Vec2 = Vec2::new(0.0, -150.0);
+// Zombie damage per second against the generator
+const GENERATOR_DPS: f32 = 10.0;
+const EXTRACTION_DELAY: f32 = 90.0;
+const EXTRACTION_HOLD: f32 = 5.0;
+const EXTRACTION_SIZE: f32 = 120.0;
+const EXTRACTION_POSITION: Vec2 = Vec2::new(0.0, 520.0);
+
+#[derive(Component)]
+pub struct Generator;
+
+#[derive(Component)]
+pub struct ExtractionZone;
+
+// Anything a game mode spawns for the run
+#[derive(Component)]
+struct ObjectiveEntity;
+
+pub fn setup_game_mode(
+    mut commands: Commands,
+    mut mode_state: ResMut<ModeState>,
+    mut objective: ResMut<Objective>,
+    settings: Res<crate::plugins::settings::GameSettings>,
+) {
+    *mode_state = ModeState::default();
+    objective.text.clear();
+
+    if settings.game_mode == GameMode::DefendGenerator {
+        let size = Vec2::splat(GENERATOR_SIZE);
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.0, 0.8, 0.8), // Cyan Generator
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(GENERATOR_POSITION.extend(0.5)),
+                ..default()
+            },
+            // Solid, so zombies pile up against it and bullets don't pass through
+            Wall,
+            Collider { size },
+            Health {
+                current: GENERATOR_HEALTH,
+                max: GENERATOR_HEALTH,
+            },
+            Generator,
+            ZombieTarget,
+            ObjectiveEntity,
+        ));
+    }
+}
+
+fn cleanup_game_mode(mut commands: Commands, query: Query<Entity, With<ObjectiveEntity>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn tick_mode_clock(time: Res<Time>, mut mode_state: ResMut<ModeState>) {
+    mode_state.elapsed += time.delta_seconds();
+}
+
+// Every mode is lost once nobody is left standing to revive the others
+fn player_death_rule(
+    mut commands: Commands,
+    player_query: Query<(Entity, Has<Downed>), With<Player>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if player_query.is_empty() || !player_query.iter().all(|(_, downed)| downed) {
+        return;
+    }
+
+    info!("Player Died!");
+    for (entity, _) in &player_query {
+        commands.entity(entity).despawn();
+    }
+    next_state.set(GameState::GameOver);
+}
+
+fn clear_waves_rule(
+    mut events: EventReader<AllWavesCleared>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut objective: ResMut<Objective>,
+    wave_state: Res<crate::plugins::wave::WaveState>,
+) {
+    objective.text = match wave_state.total_waves {
+        Some(total) => format!("Clear all {} waves", total),
+        None => "Survive as long as you can".to_string(),
+    };
+
+    if events.read().next().is_some() {
+        info!("YOU WIN!");
+        next_state.set(GameState::Win);
+    }
+}
+
+fn survival_rule(
+    mode_state: Res<ModeState>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut objective: ResMut<Objective>,
+) {
+    let remaining = (SURVIVAL_SECONDS - mode_state.elapsed).max(0.0);
+    objective.text = format!("Survive: {}", format_clock(remaining));
+
+    if remaining <= 0.0 {
+        info!("Survived! YOU WIN!");
+        next_state.set(GameState::Win);
+    }
+}
+
+fn defend_generator_rule(
+    time: Res<Time>,
+    mode_state: Res<ModeState>,
+    mut generator_query: Query<(&Transform, &mut Health), (With<Generator>, Without<Zombie>)>,
+    zombie_query: Query<(&Transform, &Sprite), With<Zombie>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut objective: ResMut<Objective>,
+) {
+    let Ok((g_transform, mut g_health)) = generator_query.get_single_mut() else {
+        return;
+    };
+
+    // Zombies touching the generator chew on it
+    for (z_transform, z_sprite) in &zombie_query {
+        let z_size = z_sprite.custom_size.unwrap_or(Vec2::splat(20.0)).x;
+        let reach = (GENERATOR_SIZE + z_size) / 2.0 + 5.0;
+        let offset = (z_transform.translation - g_transform.translation)
+            .truncate()
+            .abs();
+        if offset.x < reach && offset.y < reach {
+            g_health.current -= GENERATOR_DPS * time.delta_seconds();
+        }
+    }
+
+    let remaining = (DEFEND_SECONDS - mode_state.elapsed).max(0.0);
+    objective.text = format!(
+        "Defend the generator: {} | Generator: {:.0}/{:.0}",
+        format_clock(remaining),
+        g_health.current.max(0.0),
+        g_health.max
+    );
+
+    if g_health.current <= 0.0 {
+        info!("Generator Destroyed!");
+        next_state.set(GameState::GameOver);
+    } else if remaining <= 0.0 {
+        info!("Generator Held! YOU WIN!");
+        next_state.set(GameState::Win);
+    }
+}
+
+fn extraction_rule(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut mode_state: ResMut<ModeState>,
+    zone_query: Query<&Transform, With<ExtractionZone>>,
+    player_query: Query<&Transform, (With<Player>, Without<Downed>)>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut objective: ResMut<Objective>,
+) {
+    let until_extraction = EXTRACTION_DELAY - mode_state.elapsed;
+    if until_extraction > 0.0 {
+        objective.text = format!("Extraction arrives in {}", format_clock(until_extraction));
+        return;
+    }
+
+    let Ok(zone_transform) = zone_query.get_single() else {
+        info!("Extraction has arrived!");
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgba(0.0, 1.0, 0.0, 0.25), // Green Zone
+                    custom_size: Some(Vec2::splat(EXTRACTION_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_translation(EXTRACTION_POSITION.extend(0.1)),
+                ..default()
+            },
+            ExtractionZone,
+            ObjectiveEntity,
+        ));
+        objective.text = "Reach the extraction zone!".to_string();
+        return;
+    };
+
+    // Everyone still standing has to make it, downed players are carried out
+    let in_zone = !player_query.is_empty()
+        && player_query.iter().all(|p_transform| {
+            let offset = (p_transform.translation - zone_transform.translation)
+                .truncate()
+                .abs();
+            offset.x < EXTRACTION_SIZE / 2.0 && offset.y < EXTRACTION_SIZE / 2.0
+        });
+
+    // Leaving the zone resets the hold
+    if in_zone {
+        mode_state.extraction_hold += time.delta_seconds();
+        objective.text = format!(
+            "Extracting... {:.0}s",
+            (EXTRACTION_HOLD - mode_state.extraction_hold)
+                .max(0.0)
+                .ceil()
+        );
+    } else {
+        mode_state.extraction_hold = 0.0;
+        objective.text = "Reach the extraction zone!".to_string();
+    }
+
+    if mode_state.extraction_hold >= EXTRACTION_HOLD {
+        info!("Extracted! YOU WIN!");
+        next_state.set(GameState::Win);
+    }
+}
+
+fn format_clock(seconds: f32) -> String {
+    let total = seconds.ceil() as u32;
+    format!("{}:{:02}", total / 60, total % 60)
+}
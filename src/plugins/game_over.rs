@@ -18,7 +18,11 @@ impl Plugin for GameOverPlugin {
 #[derive(Component)]
 struct GameOverUI;
 
-fn setup_game_over(mut commands: Commands) {
+fn setup_game_over(
+    mut commands: Commands,
+    rng: Res<crate::plugins::rng::GameRng>,
+    bindings: Res<crate::plugins::controls::Keybindings>,
+) {
     commands
         .spawn((
             NodeBundle {
@@ -45,17 +49,30 @@ fn setup_game_over(mut commands: Commands) {
                 },
             ));
             parent.spawn(TextBundle::from_section(
-                "Press SPACE for Menu",
+                menu_hint(&bindings),
                 TextStyle {
                     font_size: 40.0,
                     color: Color::srgb(1.0, 1.0, 1.0),
                     ..default()
                 },
             ));
+            // Type this on the dashboard to play the same run again
+            parent.spawn(TextBundle::from_section(
+                format!("Seed: {}", rng.seed()),
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::srgb(0.8, 0.8, 0.8),
+                    ..default()
+                },
+            ));
         });
 }
 
-fn setup_win(mut commands: Commands) {
+fn setup_win(
+    mut commands: Commands,
+    rng: Res<crate::plugins::rng::GameRng>,
+    bindings: Res<crate::plugins::controls::Keybindings>,
+) {
     commands
         .spawn((
             NodeBundle {
@@ -82,16 +99,34 @@ fn setup_win(mut commands: Commands) {
                 },
             ));
             parent.spawn(TextBundle::from_section(
-                "Press SPACE for Menu",
+                menu_hint(&bindings),
                 TextStyle {
                     font_size: 40.0,
                     color: Color::srgb(1.0, 1.0, 1.0),
                     ..default()
                 },
             ));
+            // Type this on the dashboard to play the same run again
+            parent.spawn(TextBundle::from_section(
+                format!("Seed: {}", rng.seed()),
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::srgb(0.8, 0.8, 0.8),
+                    ..default()
+                },
+            ));
         });
 }
 
+fn menu_hint(bindings: &crate::plugins::controls::Keybindings) -> String {
+    format!(
+        "Press {} for Menu",
+        bindings
+            .describe(crate::plugins::controls::Action::Confirm)
+            .to_uppercase()
+    )
+}
+
 fn cleanup_game_over(mut commands: Commands, query: Query<Entity, With<GameOverUI>>) {
     for entity in &query {
         commands.entity(entity).despawn_recursive();
@@ -100,25 +135,28 @@ fn cleanup_game_over(mut commands: Commands, query: Query<Entity, With<GameOverU
 
 fn restart_game(
     mut next_state: ResMut<NextState<GameState>>,
-    input: Res<ButtonInput<KeyCode>>,
+    actions: crate::plugins::controls::ActionInput,
     mut wallet: ResMut<crate::plugins::shop::Wallet>,
-    mut wave_state: ResMut<crate::plugins::wave::WaveState>,
     mut global_stats: ResMut<crate::plugins::progression::GlobalPlayerStats>,
+    replay: Res<crate::plugins::replay::ReplayState>,
+    hosting: Option<Res<crate::plugins::net_server::NetServer>>,
 ) {
-    if input.just_pressed(KeyCode::Space) {
-        // Save Money to Global
-        global_stats.total_money += wallet.money;
+    if actions.just_pressed(crate::plugins::controls::Action::Confirm) {
+        // Save Money to Global (watching a replay doesn't pay out)
+        if !replay.is_playing_back() {
+            global_stats.total_money += wallet.money;
+        }
 
         // Reset In-Game Resources
         wallet.money = 0;
-        *wave_state = crate::plugins::wave::WaveState {
-            current_wave: 1,
-            zombies_remaining: 10,
-            spawn_timer: Timer::from_seconds(2.0, TimerMode::Repeating),
-        };
+        // WaveState is reset by the WavePlugin in RunTeardown
 
-        // Transition to Dashboard
-        // Cleanup of entities happens OnEnter(Dashboard)
-        next_state.set(GameState::Dashboard);
+        // Transition to Dashboard (or back to the lobby when hosting)
+        // Cleanup of entities happens in RunTeardown
+        next_state.set(if hosting.is_some() {
+            GameState::Lobby
+        } else {
+            GameState::Dashboard
+        });
     }
 }
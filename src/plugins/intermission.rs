@@ -0,0 +1,154 @@
+use crate::plugins::controls::{Action, Keybindings, PlayerInput};
+use crate::plugins::player::{Player, WeaponStats};
+use crate::plugins::wave::WaveState;
+use crate::plugins::wave_script::WaveScripts;
+use crate::states::WavePhase;
+use bevy::prelude::*;
+
+pub struct IntermissionPlugin;
+
+impl Plugin for IntermissionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(WavePhase::Intermission), setup_intermission_banner)
+            .add_systems(OnExit(WavePhase::Intermission), cleanup_intermission_banner)
+            .add_systems(
+                Update,
+                (
+                    intermission_countdown,
+                    intermission_reload,
+                    update_intermission_banner,
+                )
+                    .run_if(in_state(WavePhase::Intermission)),
+            );
+    }
+}
+
+#[derive(Component)]
+struct IntermissionUI;
+
+#[derive(Component)]
+struct CountdownText;
+
+fn intermission_countdown(
+    inputs: Query<&PlayerInput>,
+    time: Res<Time>,
+    mut wave_state: ResMut<WaveState>,
+    mut next_phase: ResMut<NextState<WavePhase>>,
+    settings: Res<crate::plugins::settings::GameSettings>,
+    scripts: Res<WaveScripts>,
+) {
+    let Some(timer) = wave_state.intermission.as_mut() else {
+        next_phase.set(WavePhase::Combat);
+        return;
+    };
+
+    timer.tick(time.delta());
+
+    // ENTER skips whatever is left of the countdown, any player can call it
+    if timer.finished() || inputs.iter().any(|input| input.skip_intermission) {
+        let next_wave = wave_state.current_wave + 1;
+        let Some(definition) = scripts.wave(&settings, next_wave) else {
+            return;
+        };
+
+        wave_state.intermission = None;
+        wave_state.start_wave(next_wave, scripts.total_waves(&settings), &definition);
+        next_phase.set(WavePhase::Combat);
+        info!("Starting Wave {}", next_wave);
+    }
+}
+
+fn intermission_reload(mut query: Query<(&PlayerInput, &mut WeaponStats), With<Player>>) {
+    for (input, mut stats) in &mut query {
+        if !input.reload {
+            continue;
+        }
+        stats.current_ammo = stats.max_ammo;
+        info!("Reloaded! Ammo: {}/{}", stats.current_ammo, stats.max_ammo);
+    }
+}
+
+fn setup_intermission_banner(
+    mut commands: Commands,
+    run_modifiers: Res<crate::plugins::run_modifiers::RunModifiers>,
+    bindings: Res<Keybindings>,
+) {
+    let key = |action| bindings.describe(action).to_uppercase();
+    let hint = if run_modifiers.is_active(crate::plugins::run_modifiers::Mutator::NoShop) {
+        format!(
+            "[{}] Reload   [{}] Ready",
+            key(Action::Reload),
+            key(Action::Confirm)
+        )
+    } else {
+        format!(
+            "[{}] Reload   [{}] Fire Rate   [{}] Medkit   [{}] Ready",
+            key(Action::Reload),
+            key(Action::BuyUpgrade),
+            key(Action::BuyMedkit),
+            key(Action::Confirm)
+        )
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(60.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+                ..default()
+            },
+            IntermissionUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "NEXT WAVE IN 10",
+                    TextStyle {
+                        font_size: 50.0,
+                        color: Color::srgb(1.0, 0.8, 0.0),
+                        ..default()
+                    },
+                ),
+                CountdownText,
+            ));
+            parent.spawn(TextBundle::from_section(
+                hint,
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn update_intermission_banner(
+    wave_state: Res<WaveState>,
+    mut query: Query<&mut Text, With<CountdownText>>,
+) {
+    let Some(timer) = &wave_state.intermission else {
+        return;
+    };
+
+    for mut text in &mut query {
+        text.sections[0].value = format!(
+            "WAVE {} IN {:.0}",
+            wave_state.current_wave + 1,
+            timer.remaining_secs().ceil()
+        );
+    }
+}
+
+fn cleanup_intermission_banner(mut commands: Commands, query: Query<Entity, With<IntermissionUI>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
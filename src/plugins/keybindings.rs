@@ -0,0 +1,353 @@
+use crate::plugins::controls::{Action, Binding, Keybindings};
+use crate::plugins::ui::{spawn_button, spawn_tagged_button};
+use crate::states::DashboardScreen;
+use bevy::prelude::*;
+
+pub struct KeybindingsPlugin;
+
+impl Plugin for KeybindingsPlugin {
+    fn build(&self, app: &mut App) {
+        let bindings = crate::storage::load::<Keybindings>(BINDINGS_SAVE)
+            .map(Keybindings::fill_missing)
+            .unwrap_or_default();
+
+        app.insert_resource(bindings)
+            .init_resource::<Rebinding>()
+            .add_systems(OnEnter(DashboardScreen::Controls), setup_controls_menu)
+            .add_systems(OnExit(DashboardScreen::Controls), cleanup_controls_menu)
+            .add_systems(
+                Update,
+                // Capture before handling clicks, so the click that starts a
+                // rebind isn't taken as the new binding
+                (
+                    capture_binding,
+                    controls_menu_interactions,
+                    update_controls_menu,
+                    controls_menu_visuals,
+                )
+                    .chain()
+                    .run_if(in_state(DashboardScreen::Controls)),
+            )
+            .add_systems(
+                Update,
+                save_bindings.run_if(resource_changed::<Keybindings>),
+            );
+    }
+}
+
+const BINDINGS_SAVE: &str = "keybindings";
+// A rebind gives up if nothing is pressed for this long
+const CAPTURE_SECONDS: f32 = 5.0;
+
+// The action waiting for a new key, and what to tell the player about it
+#[derive(Resource, Default)]
+struct Rebinding {
+    action: Option<Action>,
+    timer: Timer,
+    message: Option<String>,
+}
+
+#[derive(Component)]
+struct ControlsUI;
+
+#[derive(Component)]
+enum ControlsButton {
+    Rebind(Action),
+    ResetDefaults,
+    Back,
+}
+
+#[derive(Component)]
+struct BindingText(Action);
+
+#[derive(Component)]
+struct StatusText;
+
+fn save_bindings(bindings: Res<Keybindings>) {
+    // Skip the write triggered by inserting the resource at startup
+    if bindings.is_added() {
+        return;
+    }
+    crate::storage::save(BINDINGS_SAVE, &*bindings);
+}
+
+fn setup_controls_menu(mut commands: Commands, bindings: Res<Keybindings>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 1.0).into(), // Dark Grey Background
+                ..default()
+            },
+            ControlsUI,
+        ))
+        .with_children(|parent| {
+            // Title
+            parent.spawn(TextBundle::from_section(
+                "CONTROLS",
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::srgb(0.0, 1.0, 0.0), // Green Title
+                    ..default()
+                },
+            ));
+
+            // Two columns of "Label [Binding]" rows
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(40.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|columns| {
+                    let (left, right) = Action::ALL.split_at(Action::ALL.len().div_ceil(2));
+                    for actions in [left, right] {
+                        columns
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    row_gap: Val::Px(6.0),
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|column| {
+                                for &action in actions {
+                                    spawn_binding_row(column, action, &bindings);
+                                }
+                            });
+                    }
+                });
+
+            parent.spawn((
+                TextBundle::from_section(
+                    "Click an action to rebind it",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                StatusText,
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    spawn_button(
+                        row,
+                        "Reset to Defaults",
+                        200.0,
+                        ControlsButton::ResetDefaults,
+                    );
+                    spawn_button(row, "Back", 200.0, ControlsButton::Back);
+                });
+        });
+}
+
+fn spawn_binding_row(parent: &mut ChildBuilder, action: Action, bindings: &Keybindings) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn(
+                TextBundle::from_section(
+                    action.label(),
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    width: Val::Px(150.0),
+                    ..default()
+                }),
+            );
+            spawn_tagged_button(
+                row,
+                bindings.describe(action),
+                200.0,
+                ControlsButton::Rebind(action),
+                BindingText(action),
+            );
+        });
+}
+
+fn cleanup_controls_menu(
+    mut commands: Commands,
+    query: Query<Entity, With<ControlsUI>>,
+    mut rebinding: ResMut<Rebinding>,
+    navigation: Option<ResMut<crate::plugins::menu_navigation::MenuNavigation>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+    *rebinding = Rebinding::default();
+    if let Some(mut navigation) = navigation {
+        navigation.paused = false;
+    }
+}
+
+// While an action is waiting, the first key, mouse or pad button pressed
+// becomes its binding, unless another action already uses it
+fn capture_binding(
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    pad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<Keybindings>,
+    navigation: Option<ResMut<crate::plugins::menu_navigation::MenuNavigation>>,
+) {
+    // Pad presses are for the binding while one is waiting, not the menu
+    if let Some(mut navigation) = navigation {
+        navigation.paused = rebinding.action.is_some();
+    }
+    let Some(action) = rebinding.action else {
+        return;
+    };
+
+    let pressed = keyboard
+        .get_just_pressed()
+        .next()
+        .map(|key| Binding::Key(*key))
+        .or_else(|| {
+            mouse
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Mouse(*button))
+        })
+        .or_else(|| {
+            pad_buttons
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Gamepad(button.button_type))
+        });
+
+    let Some(binding) = pressed else {
+        rebinding.timer.tick(time.delta());
+        if rebinding.timer.finished() {
+            rebinding.action = None;
+            rebinding.message = Some(format!("{} unchanged", action.label()));
+        }
+        return;
+    };
+
+    if let Some(other) = bindings.conflict(binding, action) {
+        rebinding.message = Some(format!(
+            "{} is already used by {}, pick another",
+            binding.label(),
+            other.label()
+        ));
+        rebinding.timer.reset();
+        return;
+    }
+
+    bindings.rebind(action, binding);
+    info!("{} bound to {}", action.label(), binding.label());
+    rebinding.action = None;
+    rebinding.message = None;
+}
+
+fn controls_menu_interactions(
+    interaction_query: Query<(&Interaction, &ControlsButton), (Changed<Interaction>, With<Button>)>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<Keybindings>,
+    mut next_screen: ResMut<NextState<DashboardScreen>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            ControlsButton::Rebind(action) => {
+                *rebinding = Rebinding {
+                    action: Some(*action),
+                    timer: Timer::from_seconds(CAPTURE_SECONDS, TimerMode::Once),
+                    message: None,
+                };
+            }
+            ControlsButton::ResetDefaults => {
+                *bindings = Keybindings::default();
+                *rebinding = Rebinding::default();
+            }
+            ControlsButton::Back => next_screen.set(DashboardScreen::Main),
+        }
+    }
+}
+
+fn update_controls_menu(
+    bindings: Res<Keybindings>,
+    rebinding: Res<Rebinding>,
+    mut binding_texts: Query<(&mut Text, &BindingText), Without<StatusText>>,
+    mut status_text: Query<&mut Text, With<StatusText>>,
+) {
+    if !bindings.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+
+    for (mut text, BindingText(action)) in &mut binding_texts {
+        text.sections[0].value = if rebinding.action == Some(*action) {
+            "...".to_string()
+        } else {
+            bindings.describe(*action)
+        };
+    }
+
+    let status = match (&rebinding.message, rebinding.action) {
+        (Some(message), _) => message.clone(),
+        (None, Some(action)) => format!(
+            "Press a key, mouse or pad button for {} ({:.0})",
+            action.label(),
+            rebinding.timer.remaining_secs().ceil()
+        ),
+        (None, None) => "Click an action to rebind it".to_string(),
+    };
+    for mut text in &mut status_text {
+        text.sections[0].value = status.clone();
+    }
+}
+
+fn controls_menu_visuals(
+    rebinding: Res<Rebinding>,
+    mut query: Query<(&Interaction, &ControlsButton, &mut BackgroundColor), With<Button>>,
+) {
+    for (interaction, button, mut color) in &mut query {
+        let waiting =
+            matches!(button, ControlsButton::Rebind(action) if rebinding.action == Some(*action));
+        *color = if waiting {
+            Color::srgb(0.5, 0.4, 0.0).into()
+        } else if *interaction == Interaction::Hovered {
+            Color::srgb(0.3, 0.3, 0.3).into()
+        } else {
+            Color::srgb(0.2, 0.2, 0.2).into()
+        };
+    }
+}
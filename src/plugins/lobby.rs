@@ -0,0 +1,643 @@
+use crate::network::{DEFAULT_PORT, MAX_CHAT_LENGTH, Session};
+use crate::plugins::controls::{Action, ActionInput};
+use crate::plugins::net_client::{ConnectionStatus, NetClient, NetEntity, client_status};
+use crate::plugins::net_server::NetServer;
+use crate::plugins::settings::{Difficulty, GameSettings};
+use crate::plugins::ui::{spawn_button, spawn_tagged_button};
+use crate::plugins::world::section_name;
+use crate::states::GameState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use std::net::{Ipv4Addr, ToSocketAddrs};
+
+// Online co-op from the game itself: host a session or join one by address,
+// ready up, chat, and the host picks the difficulty, mode and layout and starts.
+pub struct LobbyPlugin;
+
+impl Plugin for LobbyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LobbyForm>()
+            .add_systems(OnEnter(GameState::Lobby), setup_lobby)
+            .add_systems(OnExit(GameState::Lobby), cleanup_lobby)
+            .add_systems(
+                Update,
+                (
+                    (lobby_back, lobby_typing).chain(),
+                    lobby_interactions,
+                    lobby_visuals,
+                    update_lobby_sections,
+                    update_lobby_text,
+                )
+                    .run_if(in_state(GameState::Lobby)),
+            )
+            .add_systems(Update, leave_online_run.run_if(in_state(GameState::Online)));
+    }
+}
+
+// Longest address that can be typed in
+const MAX_ADDRESS_LENGTH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LobbyField {
+    Address,
+    Chat,
+}
+
+#[derive(Resource)]
+struct LobbyForm {
+    // host:port to join, hosting only uses the port
+    address: String,
+    chat: String,
+    editing: Option<LobbyField>,
+    // Why the last Host or Join didn't work
+    error: Option<String>,
+}
+
+impl Default for LobbyForm {
+    fn default() -> Self {
+        Self {
+            address: format!("127.0.0.1:{}", DEFAULT_PORT),
+            chat: String::new(),
+            editing: None,
+            error: None,
+        }
+    }
+}
+
+#[derive(Component)]
+struct LobbyUI;
+
+#[derive(Component, Clone, Copy)]
+enum LobbyButton {
+    EditAddress,
+    Host,
+    Join,
+    CycleDifficulty,
+    CycleMode,
+    CycleLayout,
+    EditChat,
+    ToggleReady,
+    Start,
+    Leave,
+}
+
+// Parts of the screen that only show some of the time
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum LobbySection {
+    // Address, Host and Join, before there's a session
+    Connect,
+    Session,
+    HostOnly,
+    GuestOnly,
+}
+
+#[derive(Component, Clone, Copy)]
+enum LobbyText {
+    Status,
+    Address,
+    Members,
+    Difficulty,
+    Mode,
+    Layout,
+    ChatLog,
+    Chat,
+    Ready,
+    Leave,
+}
+
+fn setup_lobby(mut commands: Commands, mut form: ResMut<LobbyForm>) {
+    form.editing = None;
+    form.error = None;
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 1.0).into(),
+                ..default()
+            },
+            LobbyUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "ONLINE CO-OP",
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::srgb(0.0, 1.0, 0.0),
+                    ..default()
+                },
+            ));
+            spawn_text(parent, LobbyText::Status, 20.0);
+
+            spawn_row(parent, Some(LobbySection::Connect), |row| {
+                spawn_tagged_button(row, "", 320.0, LobbyButton::EditAddress, LobbyText::Address);
+                spawn_button(row, "Host", 120.0, LobbyButton::Host);
+                spawn_button(row, "Join", 120.0, LobbyButton::Join);
+            });
+
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            row_gap: Val::Px(10.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    LobbySection::Session,
+                ))
+                .with_children(|panel| {
+                    spawn_text(panel, LobbyText::Members, 20.0);
+                    spawn_row(panel, None, |row| {
+                        spawn_tagged_button(
+                            row,
+                            "",
+                            240.0,
+                            LobbyButton::CycleDifficulty,
+                            LobbyText::Difficulty,
+                        );
+                        spawn_tagged_button(
+                            row,
+                            "",
+                            240.0,
+                            LobbyButton::CycleMode,
+                            LobbyText::Mode,
+                        );
+                        spawn_tagged_button(
+                            row,
+                            "",
+                            240.0,
+                            LobbyButton::CycleLayout,
+                            LobbyText::Layout,
+                        );
+                    });
+                    spawn_text(panel, LobbyText::ChatLog, 18.0);
+                    spawn_row(panel, None, |row| {
+                        spawn_tagged_button(row, "", 500.0, LobbyButton::EditChat, LobbyText::Chat);
+                    });
+                    spawn_row(panel, Some(LobbySection::GuestOnly), |row| {
+                        spawn_tagged_button(
+                            row,
+                            "",
+                            200.0,
+                            LobbyButton::ToggleReady,
+                            LobbyText::Ready,
+                        );
+                    });
+                    spawn_row(panel, Some(LobbySection::HostOnly), |row| {
+                        spawn_button(row, "Start", 200.0, LobbyButton::Start);
+                    });
+                });
+
+            spawn_row(parent, None, |row| {
+                spawn_tagged_button(row, "", 200.0, LobbyButton::Leave, LobbyText::Leave);
+            });
+        });
+}
+
+fn spawn_row(
+    parent: &mut ChildBuilder,
+    section: Option<LobbySection>,
+    children: impl FnOnce(&mut ChildBuilder),
+) {
+    let mut row = parent.spawn(NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            ..default()
+        },
+        ..default()
+    });
+    if let Some(section) = section {
+        row.insert(section);
+    }
+    row.with_children(children);
+}
+
+fn spawn_text(parent: &mut ChildBuilder, text: LobbyText, font_size: f32) {
+    parent.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size,
+                color: Color::srgb(0.8, 0.8, 0.8),
+                ..default()
+            },
+        ),
+        text,
+    ));
+}
+
+fn cleanup_lobby(mut commands: Commands, query: Query<Entity, With<LobbyUI>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Back (ESC) leaves the session, or just stops typing
+fn lobby_back(
+    mut commands: Commands,
+    actions: ActionInput,
+    form: Res<LobbyForm>,
+    server: Option<Res<NetServer>>,
+    client: Option<Res<NetClient>>,
+    net_entities: Query<Entity, With<NetEntity>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if form.editing.is_none() && actions.just_pressed(Action::Back) {
+        leave_session(&mut commands, server, client, &net_entities);
+        next_state.set(GameState::Dashboard);
+    }
+}
+
+fn lobby_typing(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut form: ResMut<LobbyForm>,
+    mut server: Option<ResMut<NetServer>>,
+    mut client: Option<ResMut<NetClient>>,
+) {
+    let Some(field) = form.editing else {
+        keyboard_events.clear();
+        return;
+    };
+
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        let (text, limit) = match field {
+            LobbyField::Address => (&mut form.address, MAX_ADDRESS_LENGTH),
+            LobbyField::Chat => (&mut form.chat, MAX_CHAT_LENGTH),
+        };
+        match &event.logical_key {
+            Key::Character(c) if text.chars().count() < limit => text.push_str(c),
+            Key::Space if text.chars().count() < limit => text.push(' '),
+            Key::Backspace => {
+                text.pop();
+            }
+            Key::Escape => form.editing = None,
+            // Chat keeps the field open for the next line
+            Key::Enter if field == LobbyField::Chat => {
+                let line = std::mem::take(&mut form.chat);
+                if let Some(server) = server.as_deref_mut() {
+                    server.say(Some(0), &line);
+                } else if let Some(client) = client.as_deref_mut() {
+                    client.say(&line);
+                }
+            }
+            Key::Enter => form.editing = None,
+            _ => {}
+        }
+    }
+}
+
+fn lobby_interactions(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &LobbyButton), (Changed<Interaction>, With<Button>)>,
+    mut form: ResMut<LobbyForm>,
+    mut settings: ResMut<GameSettings>,
+    server: Option<Res<NetServer>>,
+    mut client: Option<ResMut<NetClient>>,
+    net_entities: Query<Entity, With<NetEntity>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            // Clicking again confirms, like pressing ENTER
+            LobbyButton::EditAddress => {
+                form.editing = match form.editing {
+                    Some(LobbyField::Address) => None,
+                    _ => Some(LobbyField::Address),
+                };
+            }
+            LobbyButton::EditChat => {
+                form.editing = match form.editing {
+                    Some(LobbyField::Chat) => None,
+                    _ => Some(LobbyField::Chat),
+                };
+            }
+            LobbyButton::Host => {
+                form.editing = None;
+                // Hosting listens on every interface, only the port is used
+                let port = form
+                    .address
+                    .rsplit_once(':')
+                    .and_then(|(_, port)| port.parse().ok())
+                    .unwrap_or(DEFAULT_PORT);
+                match NetServer::bind(
+                    (Ipv4Addr::UNSPECIFIED, port).into(),
+                    settings.player_count(),
+                ) {
+                    Ok(server) => {
+                        commands.insert_resource(server);
+                        form.error = None;
+                        // Online runs are never the daily challenge
+                        settings.daily = None;
+                        settings.difficulty = Some(settings.difficulty.unwrap_or_default());
+                    }
+                    Err(err) => form.error = Some(format!("Can't host on port {}: {}", port, err)),
+                }
+            }
+            LobbyButton::Join => {
+                form.editing = None;
+                let address = form
+                    .address
+                    .to_socket_addrs()
+                    .ok()
+                    .and_then(|mut addresses| addresses.next());
+                let Some(address) = address else {
+                    form.error = Some(format!("Not an address: {}", form.address));
+                    continue;
+                };
+                match NetClient::connect(address) {
+                    Ok(client) => {
+                        commands.insert_resource(client);
+                        form.error = None;
+                    }
+                    Err(err) => form.error = Some(format!("Can't connect: {}", err)),
+                }
+            }
+            // Only the host picks, everyone else sees what they picked
+            LobbyButton::CycleDifficulty if server.is_some() => {
+                settings.difficulty = Some(next_difficulty(settings.difficulty));
+                info!("Difficulty: {:?}", settings.difficulty);
+            }
+            LobbyButton::CycleMode if server.is_some() => {
+                settings.game_mode = settings.game_mode.next();
+                info!("Game Mode: {}", settings.game_mode.label());
+            }
+            LobbyButton::CycleLayout if server.is_some() => {
+                settings.open_section = next_layout(settings.open_section);
+                info!("Layout: {}", layout_label(settings.open_section));
+            }
+            LobbyButton::CycleDifficulty | LobbyButton::CycleMode | LobbyButton::CycleLayout => {}
+            LobbyButton::ToggleReady => {
+                if let Some(client) = client.as_deref_mut() {
+                    client.ready = !client.ready;
+                }
+            }
+            LobbyButton::Start => {
+                let Some(server) = server.as_deref() else {
+                    continue;
+                };
+                if server.all_ready() {
+                    next_state.set(GameState::Playing);
+                } else {
+                    info!("Waiting for everyone to be ready");
+                }
+            }
+            LobbyButton::Leave => {
+                leave_session(&mut commands, server, client, &net_entities);
+                next_state.set(GameState::Dashboard);
+                return;
+            }
+        }
+    }
+}
+
+// The presets in order, a Custom difficulty isn't offered online
+fn next_difficulty(difficulty: Option<Difficulty>) -> Difficulty {
+    match difficulty {
+        Some(Difficulty::Easy) => Difficulty::Medium,
+        Some(Difficulty::Medium) => Difficulty::Hard,
+        Some(Difficulty::Hard) => Difficulty::Endless,
+        _ => Difficulty::Easy,
+    }
+}
+
+// Just the courtyard, then each wing open from the start
+fn next_layout(open_section: Option<u32>) -> Option<u32> {
+    match open_section {
+        None => Some(1),
+        Some(1) => Some(2),
+        _ => None,
+    }
+}
+
+fn layout_label(open_section: Option<u32>) -> String {
+    match open_section {
+        Some(section) => format!("{} open", section_name(section)),
+        None => "Courtyard only".to_string(),
+    }
+}
+
+// Hosting closes the session for everyone, a guest just drops out of it
+fn leave_session(
+    commands: &mut Commands,
+    server: Option<Res<NetServer>>,
+    client: Option<impl std::ops::Deref<Target = NetClient>>,
+    net_entities: &Query<Entity, With<NetEntity>>,
+) {
+    if let Some(server) = server {
+        info!("Closing the session");
+        server.close();
+        commands.remove_resource::<NetServer>();
+    }
+    if let Some(client) = client {
+        client.leave();
+        commands.remove_resource::<NetClient>();
+        for entity in net_entities {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// Back (ESC) while watching someone else's run leaves it
+fn leave_online_run(
+    mut commands: Commands,
+    actions: ActionInput,
+    client: Option<Res<NetClient>>,
+    net_entities: Query<Entity, With<NetEntity>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if actions.just_pressed(Action::Back) {
+        leave_session(&mut commands, None, client, &net_entities);
+        next_state.set(GameState::Dashboard);
+    }
+}
+
+fn lobby_visuals(
+    mut query: Query<(&Interaction, &LobbyButton, &mut BackgroundColor), With<Button>>,
+    form: Res<LobbyForm>,
+    client: Option<Res<NetClient>>,
+) {
+    let ready = client.is_some_and(|client| client.ready);
+    for (interaction, button, mut color) in &mut query {
+        let selected = match button {
+            LobbyButton::EditAddress => form.editing == Some(LobbyField::Address),
+            LobbyButton::EditChat => form.editing == Some(LobbyField::Chat),
+            LobbyButton::ToggleReady => ready,
+            _ => false,
+        };
+        *color = match interaction {
+            Interaction::Hovered => Color::srgb(0.3, 0.3, 0.3).into(),
+            _ if selected => Color::srgb(0.2, 0.6, 0.2).into(),
+            _ => Color::srgb(0.2, 0.2, 0.2).into(),
+        };
+    }
+}
+
+fn update_lobby_sections(
+    server: Option<Res<NetServer>>,
+    client: Option<Res<NetClient>>,
+    mut query: Query<(&LobbySection, &mut Style)>,
+) {
+    let hosting = server.is_some();
+    let joined = client.is_some();
+    for (section, mut style) in &mut query {
+        let shown = match section {
+            LobbySection::Connect => !hosting && !joined,
+            LobbySection::Session => hosting || joined,
+            LobbySection::HostOnly => hosting,
+            LobbySection::GuestOnly => joined,
+        };
+        style.display = if shown { Display::Flex } else { Display::None };
+    }
+}
+
+fn update_lobby_text(
+    form: Res<LobbyForm>,
+    settings: Res<GameSettings>,
+    server: Option<Res<NetServer>>,
+    client: Option<Res<NetClient>>,
+    mut query: Query<(&mut Text, &LobbyText)>,
+) {
+    // The host builds the session itself, a guest gets it from the host
+    let session: Option<Session> = match (&server, &client) {
+        (Some(server), _) => Some(server.session(&settings)),
+        (None, Some(client)) => client.session().cloned(),
+        (None, None) => None,
+    };
+    let me = match (&server, &client) {
+        (Some(_), _) => Some(0),
+        (None, Some(client)) => client.player,
+        (None, None) => None,
+    };
+
+    let status = if let Some(error) = &form.error {
+        error.clone()
+    } else if let Some(server) = &server {
+        format!(
+            "Hosting on port {}, others join with your address",
+            server.local_addr().port()
+        )
+    } else if let Some(client) = &client {
+        match client.status {
+            ConnectionStatus::Connected => format!("Joined {}", client.server()),
+            _ => client_status(client),
+        }
+    } else {
+        "Host a session, or type the host's address and join".to_string()
+    };
+
+    for (mut text, kind) in &mut query {
+        let value = match kind {
+            LobbyText::Status => status.clone(),
+            LobbyText::Address => typed(
+                "Address",
+                &form.address,
+                form.editing == Some(LobbyField::Address),
+            ),
+            LobbyText::Members => session
+                .as_ref()
+                .map(|session| describe_members(session, me))
+                .unwrap_or_default(),
+            LobbyText::Difficulty => format!(
+                "Difficulty: {}",
+                session
+                    .as_ref()
+                    .and_then(|session| session.difficulty)
+                    .map_or("-".to_string(), |difficulty| format!("{:?}", difficulty))
+            ),
+            LobbyText::Mode => format!(
+                "Mode: {}",
+                session
+                    .as_ref()
+                    .map_or("-", |session| session.game_mode.label())
+            ),
+            LobbyText::Layout => format!(
+                "Layout: {}",
+                session
+                    .as_ref()
+                    .map(|session| layout_label(session.open_section))
+                    .unwrap_or_else(|| "-".to_string())
+            ),
+            LobbyText::ChatLog => session
+                .as_ref()
+                .map(|session| {
+                    session
+                        .chat
+                        .iter()
+                        .map(|line| line.describe())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default(),
+            LobbyText::Chat => typed("Say", &form.chat, form.editing == Some(LobbyField::Chat)),
+            LobbyText::Ready => {
+                if client.as_ref().is_some_and(|client| client.ready) {
+                    "Ready!".to_string()
+                } else {
+                    "Ready?".to_string()
+                }
+            }
+            LobbyText::Leave => {
+                if server.is_some() || client.is_some() {
+                    "Leave".to_string()
+                } else {
+                    "Back".to_string()
+                }
+            }
+        };
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+// A text field's button label, with a cursor while it's being typed in
+fn typed(label: &str, text: &str, editing: bool) -> String {
+    if editing {
+        format!("{}: {}_", label, text)
+    } else {
+        format!("{}: {}", label, text)
+    }
+}
+
+fn describe_members(session: &Session, me: Option<usize>) -> String {
+    session
+        .members
+        .iter()
+        .map(|member| {
+            let mut line = format!("P{}", member.player + 1);
+            if member.host {
+                line.push_str(" (host)");
+            }
+            if me == Some(member.player) {
+                line.push_str(" (you)");
+            }
+            line.push_str(if member.ready {
+                " - Ready"
+            } else {
+                " - Not ready"
+            });
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,169 @@
+use bevy::input::mouse::MouseMotion;
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+pub struct MenuNavigationPlugin;
+
+impl Plugin for MenuNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        // After the UI has worked out what the mouse is over, so the focused
+        // button isn't reset straight away
+        app.init_resource::<MenuNavigation>()
+            .add_systems(PreUpdate, navigate_menus.after(UiSystem::Focus));
+    }
+}
+
+// Holding the stick moves the focus again after this long
+const STICK_REPEAT_SECONDS: f32 = 0.3;
+// How far the stick has to be pushed to move the focus
+const STICK_THRESHOLD: f32 = 0.5;
+
+// The button a gamepad is pointing at. It shows as hovered and A presses it,
+// so every menu works on a pad without knowing about it.
+#[derive(Resource, Default)]
+pub struct MenuNavigation {
+    focused: Option<Entity>,
+    // Pressed last frame, released again this frame like a click
+    pressed: Option<Entity>,
+    stick_cooldown: f32,
+    // Set while a menu wants the raw pad buttons for itself
+    pub paused: bool,
+}
+
+fn navigate_menus(
+    time: Res<Time>,
+    mut navigation: ResMut<MenuNavigation>,
+    gamepads: Res<Gamepads>,
+    pad_buttons: Res<ButtonInput<GamepadButton>>,
+    pad_axes: Res<Axis<GamepadAxis>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut buttons: Query<
+        (
+            Entity,
+            &Node,
+            &GlobalTransform,
+            &InheritedVisibility,
+            &mut Interaction,
+        ),
+        With<Button>,
+    >,
+) {
+    if let Some(entity) = navigation.pressed.take()
+        && let Ok((.., mut interaction)) = buttons.get_mut(entity)
+        && *interaction == Interaction::Pressed
+    {
+        *interaction = Interaction::Hovered;
+    }
+
+    // The mouse takes over as soon as it moves
+    if mouse_motion.read().count() > 0 {
+        navigation.focused = None;
+        return;
+    }
+
+    // Buttons on screen, by their centre in window pixels (y points down)
+    let visible: Vec<(Entity, Vec2)> = buttons
+        .iter()
+        .filter(|(_, node, _, visibility, _)| visibility.get() && node.size() != Vec2::ZERO)
+        .map(|(entity, _, transform, ..)| (entity, transform.translation().truncate()))
+        .collect();
+    if navigation
+        .focused
+        .is_some_and(|focused| !visible.iter().any(|(entity, _)| *entity == focused))
+    {
+        navigation.focused = None;
+    }
+    if navigation.paused {
+        return;
+    }
+
+    let pad_just_pressed = |button| {
+        gamepads
+            .iter()
+            .any(|pad| pad_buttons.just_pressed(GamepadButton::new(pad, button)))
+    };
+    let mut direction = [
+        (GamepadButtonType::DPadUp, Vec2::NEG_Y),
+        (GamepadButtonType::DPadDown, Vec2::Y),
+        (GamepadButtonType::DPadLeft, Vec2::NEG_X),
+        (GamepadButtonType::DPadRight, Vec2::X),
+    ]
+    .into_iter()
+    .find(|(button, _)| pad_just_pressed(*button))
+    .map(|(_, direction)| direction);
+
+    let stick = gamepads
+        .iter()
+        .map(|pad| {
+            Vec2::new(
+                pad_axes
+                    .get(GamepadAxis::new(pad, GamepadAxisType::LeftStickX))
+                    .unwrap_or(0.0),
+                // Stick up is positive, screen up is negative
+                -pad_axes
+                    .get(GamepadAxis::new(pad, GamepadAxisType::LeftStickY))
+                    .unwrap_or(0.0),
+            )
+        })
+        .find(|stick| stick.length() > STICK_THRESHOLD);
+    navigation.stick_cooldown -= time.delta_seconds();
+    match stick {
+        Some(stick) if navigation.stick_cooldown <= 0.0 => {
+            navigation.stick_cooldown = STICK_REPEAT_SECONDS;
+            direction = direction.or(Some(if stick.x.abs() > stick.y.abs() {
+                Vec2::new(stick.x.signum(), 0.0)
+            } else {
+                Vec2::new(0.0, stick.y.signum())
+            }));
+        }
+        Some(_) => {}
+        None => navigation.stick_cooldown = 0.0,
+    }
+
+    if let Some(direction) = direction {
+        navigation.focused = match navigation
+            .focused
+            .and_then(|focused| visible.iter().find(|(entity, _)| *entity == focused))
+        {
+            Some(&(current, from)) => {
+                Some(next_button(&visible, from, direction).unwrap_or(current))
+            }
+            // Nothing focused yet, start at the top left
+            None => visible
+                .iter()
+                .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+                .map(|(entity, _)| *entity),
+        };
+    }
+
+    let Some(focused) = navigation.focused else {
+        return;
+    };
+    let Ok((.., mut interaction)) = buttons.get_mut(focused) else {
+        return;
+    };
+    if pad_just_pressed(GamepadButtonType::South) {
+        *interaction = Interaction::Pressed;
+        navigation.pressed = Some(focused);
+    } else if *interaction == Interaction::None {
+        *interaction = Interaction::Hovered;
+    }
+}
+
+// The nearest button in the given direction, favouring ones in line with
+// the current one over ones off to the side
+fn next_button(visible: &[(Entity, Vec2)], from: Vec2, direction: Vec2) -> Option<Entity> {
+    visible
+        .iter()
+        .filter_map(|&(entity, position)| {
+            let offset = position - from;
+            let along = offset.dot(direction);
+            if along <= 1.0 {
+                return None;
+            }
+            let across = (offset - direction * along).length();
+            Some((entity, along + across * 2.0))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entity, _)| entity)
+}
@@ -10,3 +10,24 @@ pub mod pause;
 pub mod dashboard;
 pub mod progression;
 pub mod settings;
+pub mod doors;
+pub mod camera_effects;
+pub mod wave_script;
+pub mod intermission;
+pub mod spawning;
+pub mod game_mode;
+pub mod custom_difficulty;
+pub mod daily_challenge;
+pub mod run_modifiers;
+pub mod rng;
+pub mod controls;
+pub mod replay;
+pub mod simulation;
+pub mod bot;
+pub mod view;
+pub mod keybindings;
+pub mod menu_navigation;
+pub mod touch;
+pub mod net_client;
+pub mod net_server;
+pub mod lobby;
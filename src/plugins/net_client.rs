@@ -0,0 +1,486 @@
+use crate::components::Door;
+use crate::network::{
+    ClientMessage, NetSocket, NetSprite, SNAPSHOT_RATE, ServerMessage, Session, Snapshot,
+    TIMEOUT_SECONDS,
+};
+use crate::plugins::controls::PlayerInput;
+use crate::plugins::player::{Player, PlayerId};
+use crate::plugins::world::UnlockedSections;
+use crate::states::GameState;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+
+// The drawing side of online co-op. Nothing is simulated here: the client
+// sends its input to the server and shows the snapshots that come back,
+// blended one snapshot behind so movement stays smooth between them. Does
+// nothing until a NetClient is inserted, by the lobby or by `--connect`.
+pub struct NetClientPlugin;
+
+impl Plugin for NetClientPlugin {
+    fn build(&self, app: &mut App) {
+        // The HUD shows the server's counters through the usual resources
+        app.init_resource::<crate::plugins::wave::WaveState>()
+            .init_resource::<crate::plugins::shop::Wallet>()
+            .init_resource::<crate::plugins::game_mode::Objective>()
+            .add_systems(Startup, setup_client_hud)
+            .add_systems(
+                PreUpdate,
+                receive_server_messages.run_if(resource_exists::<NetClient>),
+            )
+            .add_systems(
+                Update,
+                (
+                    follow_server_state,
+                    sync_net_sprites,
+                    sync_doors,
+                    sync_counters,
+                    resend_session_requests,
+                )
+                    .after(receive_server_messages)
+                    .run_if(resource_exists::<NetClient>),
+            )
+            .add_systems(Update, update_client_hud)
+            // Input is read in PreUpdate, so this frame's presses go out with it
+            .add_systems(Update, send_input.run_if(resource_exists::<NetClient>))
+            .add_systems(Last, say_goodbye.run_if(resource_exists::<NetClient>));
+    }
+}
+
+// How often Hello is repeated until the server answers
+const HELLO_SECONDS: f32 = 1.0;
+// How often ready flags and chat lines are resent until the session shows them
+const RESEND_SECONDS: f32 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    Connecting,
+    Connected,
+    // The server has no free player slot
+    Full,
+    // Nothing heard from the server for a while
+    Lost,
+    // The host ended the session
+    Closed,
+}
+
+#[derive(Resource)]
+pub struct NetClient {
+    socket: NetSocket,
+    server: SocketAddr,
+    pub status: ConnectionStatus,
+    // Our player slot on the server
+    pub player: Option<usize>,
+    // Ready for the next run, sent until the session agrees
+    pub ready: bool,
+    hello_timer: Timer,
+    resend_timer: Timer,
+    // Chat lines the server hasn't confirmed yet, by id
+    unsent_chat: Vec<(u32, String)>,
+    last_chat_id: u32,
+    silent: f32,
+    previous: Option<Snapshot>,
+    pub latest: Option<Snapshot>,
+    // Seconds since `latest` arrived
+    since_latest: f32,
+}
+
+impl NetClient {
+    // Opens a socket and says hello, the answer arrives over the next frames
+    pub fn connect(server: SocketAddr) -> std::io::Result<Self> {
+        let socket = NetSocket::bind((Ipv4Addr::UNSPECIFIED, 0).into())?;
+        socket.send(server, &ClientMessage::Hello);
+        Ok(Self {
+            socket,
+            server,
+            status: ConnectionStatus::Connecting,
+            player: None,
+            ready: false,
+            hello_timer: Timer::from_seconds(HELLO_SECONDS, TimerMode::Repeating),
+            resend_timer: Timer::from_seconds(RESEND_SECONDS, TimerMode::Repeating),
+            unsent_chat: Vec::new(),
+            last_chat_id: 0,
+            silent: 0.0,
+            previous: None,
+            latest: None,
+            since_latest: 0.0,
+        })
+    }
+
+    pub fn server(&self) -> SocketAddr {
+        self.server
+    }
+
+    // The lobby as of the latest snapshot
+    pub fn session(&self) -> Option<&Session> {
+        self.latest.as_ref().map(|snapshot| &snapshot.session)
+    }
+
+    pub fn say(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        self.last_chat_id += 1;
+        let id = self.last_chat_id;
+        self.unsent_chat.push((id, text.to_string()));
+        self.socket.send(
+            self.server,
+            &ClientMessage::Chat {
+                id,
+                text: text.to_string(),
+            },
+        );
+    }
+
+    // Lets the server free our slot straight away instead of timing us out
+    pub fn leave(&self) {
+        if self.status == ConnectionStatus::Connected {
+            self.socket.send(self.server, &ClientMessage::Goodbye);
+        }
+    }
+}
+
+// Something drawn for an entity on the server
+#[derive(Component)]
+pub struct NetEntity(pub u64);
+
+fn receive_server_messages(time: Res<Time>, mut client: ResMut<NetClient>) {
+    client.silent += time.delta_seconds();
+    client.since_latest += time.delta_seconds();
+
+    while let Some((from, message)) = client.socket.receive::<ServerMessage>() {
+        if from != client.server {
+            continue;
+        }
+        client.silent = 0.0;
+        match message {
+            ServerMessage::Welcome { player } => {
+                if client.player.is_none() {
+                    info!("Joined {} as P{}", client.server, player + 1);
+                }
+                client.player = Some(player);
+                client.status = ConnectionStatus::Connected;
+            }
+            ServerMessage::Full => {
+                if client.status != ConnectionStatus::Full {
+                    warn!("Server {} is full", client.server);
+                }
+                client.status = ConnectionStatus::Full;
+            }
+            ServerMessage::Closed => {
+                if client.status != ConnectionStatus::Closed {
+                    info!("{} closed the session", client.server);
+                }
+                client.status = ConnectionStatus::Closed;
+            }
+            // Late packets are older than what we already show
+            ServerMessage::Snapshot(snapshot) => {
+                if client
+                    .latest
+                    .as_ref()
+                    .is_none_or(|latest| snapshot.tick > latest.tick)
+                {
+                    client.previous = client.latest.replace(snapshot);
+                    client.since_latest = 0.0;
+                }
+            }
+        }
+    }
+
+    match client.status {
+        ConnectionStatus::Connecting => {
+            client.hello_timer.tick(time.delta());
+            if client.hello_timer.just_finished() {
+                client.socket.send(client.server, &ClientMessage::Hello);
+            }
+        }
+        ConnectionStatus::Connected if client.silent > TIMEOUT_SECONDS => {
+            warn!("Lost connection to {}", client.server);
+            client.status = ConnectionStatus::Lost;
+        }
+        _ => {}
+    }
+
+    // Nothing left to show once the server is gone
+    if matches!(
+        client.status,
+        ConnectionStatus::Lost | ConnectionStatus::Closed
+    ) {
+        client.previous = None;
+        client.latest = None;
+    }
+}
+
+// Waits in the Lobby while the server is between runs and watches in Online
+// while one plays, ending screens included. Back to the Lobby if the server
+// goes away mid-run, to say so.
+fn follow_server_state(
+    mut client: ResMut<NetClient>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let wanted = match &client.latest {
+        Some(snapshot) => match snapshot.state {
+            GameState::Dashboard | GameState::Lobby => GameState::Lobby,
+            _ => GameState::Online,
+        },
+        None if *state.get() == GameState::Online => GameState::Lobby,
+        None => return,
+    };
+    if wanted != *state.get() {
+        // Everyone readies up again for the next run
+        if wanted == GameState::Online {
+            client.ready = false;
+        }
+        next_state.set(wanted);
+    }
+}
+
+fn sync_net_sprites(
+    mut commands: Commands,
+    client: Res<NetClient>,
+    mut existing: Query<(Entity, &NetEntity, &mut Transform, &mut Sprite)>,
+) {
+    let Some(latest) = &client.latest else {
+        for (entity, ..) in &existing {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    };
+    let previous: HashMap<u64, &NetSprite> = client
+        .previous
+        .iter()
+        .flat_map(|snapshot| &snapshot.sprites)
+        .map(|sprite| (sprite.id, sprite))
+        .collect();
+    let alpha = (client.since_latest * SNAPSHOT_RATE as f32).clamp(0.0, 1.0);
+
+    let mut shown: HashMap<u64, Entity> = HashMap::new();
+    for (entity, net_entity, ..) in &existing {
+        shown.insert(net_entity.0, entity);
+    }
+
+    for sprite in &latest.sprites {
+        let (position, rotation) = match previous.get(&sprite.id) {
+            Some(before) => (
+                before.position.lerp(sprite.position, alpha),
+                lerp_angle(before.rotation, sprite.rotation, alpha),
+            ),
+            None => (sprite.position, sprite.rotation),
+        };
+        let transform = Transform::from_translation(position.extend(sprite.z))
+            .with_rotation(Quat::from_rotation_z(rotation));
+        let color = Color::srgba(
+            sprite.color[0],
+            sprite.color[1],
+            sprite.color[2],
+            sprite.color[3],
+        );
+
+        match shown.remove(&sprite.id) {
+            Some(entity) => {
+                if let Ok((.., mut current, mut current_sprite)) = existing.get_mut(entity) {
+                    *current = transform;
+                    current_sprite.color = color;
+                    current_sprite.custom_size = Some(sprite.size);
+                }
+            }
+            None => {
+                let mut entity = commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color,
+                            custom_size: Some(sprite.size),
+                            ..default()
+                        },
+                        transform,
+                        ..default()
+                    },
+                    NetEntity(sprite.id),
+                ));
+                // Players are what the camera follows. Ours also reads the
+                // local controls, as the only player on this machine.
+                if let Some(player) = sprite.player {
+                    entity.insert(Player);
+                    if client.player == Some(player) {
+                        entity.insert((PlayerId(0), PlayerInput::default()));
+                    }
+                }
+            }
+        }
+    }
+
+    // Gone on the server
+    for entity in shown.into_values() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// The short way round, so a turn across ±180° doesn't spin the long way
+fn lerp_angle(from: f32, to: f32, t: f32) -> f32 {
+    let delta =
+        (to - from + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+    from + delta * t
+}
+
+// Bought doors open here too
+fn sync_doors(
+    mut commands: Commands,
+    client: Res<NetClient>,
+    mut unlocked: ResMut<UnlockedSections>,
+    doors: Query<(Entity, &Door)>,
+) {
+    let Some(latest) = &client.latest else {
+        return;
+    };
+    for (entity, door) in &doors {
+        if latest.unlocked.contains(&door.section) {
+            unlocked.0.insert(door.section);
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn sync_counters(
+    client: Res<NetClient>,
+    mut wave_state: ResMut<crate::plugins::wave::WaveState>,
+    mut wallet: ResMut<crate::plugins::shop::Wallet>,
+    mut objective: ResMut<crate::plugins::game_mode::Objective>,
+) {
+    let Some(latest) = &client.latest else {
+        return;
+    };
+    wave_state.current_wave = latest.wave;
+    wallet.money = latest.money;
+    objective.text.clone_from(&latest.objective);
+}
+
+// Whatever the session doesn't show yet goes out again
+fn resend_session_requests(time: Res<Time>, mut client: ResMut<NetClient>) {
+    let Some(me) = client.player.and_then(|player| {
+        client
+            .session()?
+            .members
+            .iter()
+            .find(|member| member.player == player)
+            .cloned()
+    }) else {
+        return;
+    };
+    client.unsent_chat.retain(|(id, _)| *id > me.chat_id);
+
+    client.resend_timer.tick(time.delta());
+    if !client.resend_timer.just_finished() {
+        return;
+    }
+    if me.ready != client.ready {
+        client
+            .socket
+            .send(client.server, &ClientMessage::Ready(client.ready));
+    }
+    for (id, text) in &client.unsent_chat {
+        client.socket.send(
+            client.server,
+            &ClientMessage::Chat {
+                id: *id,
+                text: text.clone(),
+            },
+        );
+    }
+}
+
+fn send_input(client: Res<NetClient>, players: Query<&PlayerInput, With<PlayerId>>) {
+    if client.status != ConnectionStatus::Connected {
+        return;
+    }
+    // Sent even without a player to steer, so the server knows we're still here
+    let input = players.iter().next().copied().unwrap_or_default();
+    client
+        .socket
+        .send(client.server, &ClientMessage::Input(input));
+}
+
+fn say_goodbye(client: Res<NetClient>, mut exit: EventReader<AppExit>) {
+    if exit.read().next().is_some() && client.status == ConnectionStatus::Connected {
+        client.socket.send(client.server, &ClientMessage::Goodbye);
+    }
+}
+
+#[derive(Component)]
+struct ClientHudText;
+
+fn setup_client_hud(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            // Under the menus, the lobby has its own status line
+            z_index: ZIndex::Global(-1),
+            ..TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                left: Val::Px(10.0),
+                ..default()
+            })
+        },
+        ClientHudText,
+    ));
+}
+
+fn update_client_hud(
+    client: Option<Res<NetClient>>,
+    mut query: Query<&mut Text, With<ClientHudText>>,
+) {
+    let text = match client {
+        Some(client) => client_status(&client),
+        None => String::new(),
+    };
+    for mut hud in &mut query {
+        hud.sections[0].value.clone_from(&text);
+    }
+}
+
+// One line on how the connection is going, the lobby shows it too
+pub fn client_status(client: &NetClient) -> String {
+    match (client.status, &client.latest) {
+        (ConnectionStatus::Connecting, _) => format!("Connecting to {}...", client.server),
+        (ConnectionStatus::Full, _) => "Server is full".to_string(),
+        (ConnectionStatus::Lost, _) => "Connection lost".to_string(),
+        (ConnectionStatus::Closed, _) => "The host closed the session".to_string(),
+        (ConnectionStatus::Connected, None) => "Connected, waiting for the server".to_string(),
+        (ConnectionStatus::Connected, Some(snapshot)) => describe(snapshot),
+    }
+}
+
+// Wave, money and the objective are on the regular HUD
+fn describe(snapshot: &Snapshot) -> String {
+    let mut lines = match snapshot.state {
+        GameState::Dashboard | GameState::Lobby => vec!["Waiting for the next run".to_string()],
+        GameState::Paused => vec!["PAUSED".to_string()],
+        GameState::GameOver => vec!["GAME OVER".to_string()],
+        GameState::Win => vec!["YOU WIN!".to_string()],
+        _ => Vec::new(),
+    };
+    for status in &snapshot.players {
+        lines.push(if status.downed {
+            format!("P{} DOWN", status.player + 1)
+        } else {
+            format!(
+                "P{} Health: {:.0}/{:.0}   Ammo: {}/{}",
+                status.player + 1,
+                status.health,
+                status.max_health,
+                status.ammo,
+                status.max_ammo
+            )
+        });
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,455 @@
+use crate::components::{Door, Health, LevelGeometry};
+use crate::network::{
+    CHAT_LINES, ChatLine, ClientMessage, MAX_CHAT_LENGTH, Member, NetSocket, NetSprite,
+    PlayerStatus, SNAPSHOT_RATE, ServerMessage, Session, Snapshot, TIMEOUT_SECONDS,
+};
+use crate::plugins::controls::{PlayerInput, read_player_input};
+use crate::plugins::player::{
+    Downed, Loadout, MAX_PLAYERS, Player, PlayerId, WeaponStats, spawn_player, spawn_players,
+    start_position,
+};
+use crate::plugins::run_modifiers::RunModifiers;
+use crate::plugins::settings::GameSettings;
+use crate::plugins::world::{UnlockedSections, open_section};
+use crate::states::{GameState, RunSetup, RunTeardown};
+use bevy::prelude::*;
+use std::net::SocketAddr;
+
+// The authoritative side of online co-op. Runs next to the gameplay plugins,
+// turns client packets into PlayerInput on their players and sends every
+// client a snapshot of what there is to see. Does nothing until a NetServer
+// is inserted, by the lobby when hosting or by the dedicated server binary.
+pub struct NetServerPlugin;
+
+impl Plugin for NetServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (receive_client_messages, apply_client_input)
+                .chain()
+                .after(read_player_input)
+                .run_if(resource_exists::<NetServer>),
+        )
+        // With no players of its own, a dedicated server's clients are what
+        // stops spawn_players adding a local one. A host's players come
+        // first and the clients join them in Update.
+        .add_systems(
+            OnEnter(GameState::Playing),
+            spawn_client_players.before(spawn_players).run_if(dedicated),
+        )
+        .add_systems(
+            Update,
+            spawn_client_players
+                .run_if(resource_exists::<NetServer>.and_then(in_state(GameState::Playing))),
+        )
+        .add_systems(
+            RunSetup,
+            open_host_section.run_if(resource_exists::<NetServer>),
+        )
+        .add_systems(
+            RunTeardown,
+            forget_client_players.run_if(resource_exists::<NetServer>),
+        )
+        .add_systems(
+            Last,
+            (send_snapshots, close_on_exit).run_if(resource_exists::<NetServer>),
+        )
+        .add_systems(Update, dedicated_flow.run_if(dedicated));
+    }
+}
+
+// Seconds on the Win/Game Over screen before a dedicated server starts over
+const RESTART_SECONDS: f32 = 5.0;
+// Snapshots keep players and cut the rest past this, to stay in one datagram
+const MAX_SPRITES: usize = 300;
+
+pub struct ClientSlot {
+    pub address: SocketAddr,
+    pub player: usize,
+    // None until the run spawns them
+    pub entity: Option<Entity>,
+    pub ready: bool,
+    // Presses are kept until a frame has applied them
+    input: PlayerInput,
+    silent: f32,
+    chat_id: u32,
+}
+
+#[derive(Resource)]
+pub struct NetServer {
+    socket: NetSocket,
+    pub clients: Vec<ClientSlot>,
+    // Slots below this are the players on this machine
+    first_slot: usize,
+    chat: Vec<ChatLine>,
+    snapshot_timer: Timer,
+    tick: u32,
+}
+
+impl NetServer {
+    // `local_players` is how many play on this machine: the host's own
+    // players (local co-op included), or none for a dedicated server
+    pub fn bind(address: SocketAddr, local_players: usize) -> std::io::Result<Self> {
+        let socket = NetSocket::bind(address)?;
+        info!("Server listening on {}", socket.local_addr());
+        Ok(Self {
+            socket,
+            clients: Vec::new(),
+            first_slot: local_players,
+            chat: Vec::new(),
+            snapshot_timer: Timer::from_seconds(1.0 / SNAPSHOT_RATE as f32, TimerMode::Repeating),
+            tick: 0,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.socket.local_addr()
+    }
+
+    // No local player and no menus: runs start as soon as someone joins and
+    // restart on their own
+    pub fn dedicated(&self) -> bool {
+        self.first_slot == 0
+    }
+
+    // The host can start once everyone who joined is ready
+    pub fn all_ready(&self) -> bool {
+        self.clients.iter().all(|client| client.ready)
+    }
+
+    pub fn chat(&self) -> &[ChatLine] {
+        &self.chat
+    }
+
+    // `player` is None for notices from the server itself
+    pub fn say(&mut self, player: Option<usize>, text: &str) {
+        let text: String = text.trim().chars().take(MAX_CHAT_LENGTH).collect();
+        if text.is_empty() {
+            return;
+        }
+        self.chat.push(ChatLine { player, text });
+        let overflow = self.chat.len().saturating_sub(CHAT_LINES);
+        self.chat.drain(..overflow);
+    }
+
+    // Who's in and what the next run plays, as clients see it
+    pub fn session(&self, settings: &GameSettings) -> Session {
+        let hosts = (0..self.first_slot).map(|player| Member {
+            player,
+            host: true,
+            ready: true,
+            chat_id: 0,
+        });
+        let clients = self.clients.iter().map(|client| Member {
+            player: client.player,
+            host: false,
+            ready: client.ready,
+            chat_id: client.chat_id,
+        });
+        let mut members: Vec<Member> = hosts.chain(clients).collect();
+        members.sort_by_key(|member| member.player);
+        Session {
+            difficulty: settings.difficulty,
+            game_mode: settings.game_mode,
+            open_section: settings.open_section,
+            members,
+            chat: self.chat.clone(),
+        }
+    }
+
+    // Tells everyone the session is over, before the server is dropped
+    pub fn close(&self) {
+        for client in &self.clients {
+            self.socket.send(client.address, &ServerMessage::Closed);
+        }
+    }
+
+    fn free_slot(&self) -> Option<usize> {
+        (self.first_slot..MAX_PLAYERS)
+            .find(|slot| !self.clients.iter().any(|client| client.player == *slot))
+    }
+}
+
+fn dedicated(server: Option<Res<NetServer>>) -> bool {
+    server.is_some_and(|server| server.dedicated())
+}
+
+fn receive_client_messages(mut commands: Commands, time: Res<Time>, mut server: ResMut<NetServer>) {
+    for client in &mut server.clients {
+        client.silent += time.delta_seconds();
+    }
+
+    while let Some((from, message)) = server.socket.receive::<ClientMessage>() {
+        let known = server
+            .clients
+            .iter()
+            .position(|client| client.address == from);
+        match (message, known) {
+            (ClientMessage::Hello, Some(index)) => {
+                let player = server.clients[index].player;
+                server.socket.send(from, &ServerMessage::Welcome { player });
+            }
+            (ClientMessage::Hello, None) => {
+                let Some(player) = server.free_slot() else {
+                    server.socket.send(from, &ServerMessage::Full);
+                    continue;
+                };
+                info!("P{} joined from {}", player + 1, from);
+                server.clients.push(ClientSlot {
+                    address: from,
+                    player,
+                    entity: None,
+                    ready: false,
+                    input: PlayerInput::default(),
+                    silent: 0.0,
+                    chat_id: 0,
+                });
+                server.say(None, &format!("P{} joined", player + 1));
+                server.socket.send(from, &ServerMessage::Welcome { player });
+            }
+            (ClientMessage::Input(input), Some(index)) => {
+                let client = &mut server.clients[index];
+                client.silent = 0.0;
+                merge_input(&mut client.input, input);
+            }
+            (ClientMessage::Ready(ready), Some(index)) => {
+                server.clients[index].ready = ready;
+            }
+            (ClientMessage::Chat { id, text }, Some(index)) => {
+                let client = &mut server.clients[index];
+                if id <= client.chat_id {
+                    continue;
+                }
+                client.chat_id = id;
+                let player = client.player;
+                server.say(Some(player), &text);
+            }
+            (ClientMessage::Goodbye, Some(index)) => {
+                let client = server.clients.remove(index);
+                info!("P{} left", client.player + 1);
+                server.say(None, &format!("P{} left", client.player + 1));
+                drop_client(&mut commands, &client);
+            }
+            // Inputs from someone who never said hello (or was timed out)
+            (_, None) => {}
+        }
+    }
+
+    let (gone, staying) = std::mem::take(&mut server.clients)
+        .into_iter()
+        .partition(|client| client.silent > TIMEOUT_SECONDS);
+    server.clients = staying;
+    for client in gone {
+        info!("P{} timed out", client.player + 1);
+        server.say(None, &format!("P{} lost connection", client.player + 1));
+        drop_client(&mut commands, &client);
+    }
+}
+
+// Their player leaves with them, the run goes on for everyone else
+fn drop_client(commands: &mut Commands, client: &ClientSlot) {
+    if let Some(entity) = client.entity {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Held controls follow the latest packet, presses stay set until applied
+fn merge_input(pending: &mut PlayerInput, latest: PlayerInput) {
+    *pending = PlayerInput {
+        weapon: latest.weapon.or(pending.weapon),
+        cycle_weapon: latest.cycle_weapon || pending.cycle_weapon,
+        interact: latest.interact || pending.interact,
+        reload: latest.reload || pending.reload,
+        buy_upgrade: latest.buy_upgrade || pending.buy_upgrade,
+        buy_medkit: latest.buy_medkit || pending.buy_medkit,
+        skip_intermission: latest.skip_intermission || pending.skip_intermission,
+        // One player can't pause a shared run
+        pause: false,
+        ..latest
+    };
+}
+
+// Overrides whatever the local devices said for remote players
+fn apply_client_input(mut server: ResMut<NetServer>, mut players: Query<&mut PlayerInput>) {
+    for client in &mut server.clients {
+        let Some(mut input) = client
+            .entity
+            .and_then(|entity| players.get_mut(entity).ok())
+        else {
+            continue;
+        };
+        *input = client.input;
+        client.input = PlayerInput {
+            movement: client.input.movement,
+            aim: client.input.aim,
+            aim_assist: client.input.aim_assist,
+            fire: client.input.fire,
+            ..default()
+        };
+    }
+}
+
+// Everyone connected gets a player, including anyone joining mid-run
+fn spawn_client_players(
+    mut commands: Commands,
+    mut server: ResMut<NetServer>,
+    loadout: Res<Loadout>,
+    settings: Res<GameSettings>,
+    run_modifiers: Res<RunModifiers>,
+) {
+    for client in &mut server.clients {
+        if client.entity.is_some() {
+            continue;
+        }
+        let entity = spawn_player(
+            &mut commands,
+            PlayerId(client.player),
+            start_position(client.player, MAX_PLAYERS),
+            &loadout,
+            &settings,
+            &run_modifiers,
+        );
+        client.entity = Some(entity);
+    }
+}
+
+// The wing the host picked in the lobby. Clients open it like any bought
+// door, from the unlocked sections in the snapshots.
+fn open_host_section(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    doors: Query<(Entity, &Door)>,
+    mut unlocked: ResMut<UnlockedSections>,
+) {
+    if let Some(section) = settings.open_section {
+        open_section(&mut commands, &doors, &mut unlocked, section);
+    }
+}
+
+// The level (and every player in it) is torn down on the way back to the
+// menu, and everyone readies up again for the next run
+fn forget_client_players(mut server: ResMut<NetServer>) {
+    for client in &mut server.clients {
+        client.entity = None;
+        client.ready = false;
+    }
+}
+
+fn send_snapshots(
+    time: Res<Time>,
+    mut server: ResMut<NetServer>,
+    state: Res<State<GameState>>,
+    wave_state: Option<Res<crate::plugins::wave::WaveState>>,
+    wallet: Option<Res<crate::plugins::shop::Wallet>>,
+    objective: Option<Res<crate::plugins::game_mode::Objective>>,
+    unlocked: Option<Res<crate::plugins::world::UnlockedSections>>,
+    settings: Res<GameSettings>,
+    players: Query<(&PlayerId, &Health, &WeaponStats, Has<Downed>), With<Player>>,
+    sprites: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Sprite,
+            Option<&PlayerId>,
+            Option<&Visibility>,
+        ),
+        Without<LevelGeometry>,
+    >,
+) {
+    server.snapshot_timer.tick(time.delta());
+    if !server.snapshot_timer.just_finished() || server.clients.is_empty() {
+        return;
+    }
+    server.tick += 1;
+
+    let mut players: Vec<PlayerStatus> = players
+        .iter()
+        .map(|(id, health, stats, downed)| PlayerStatus {
+            player: id.0,
+            health: health.current.max(0.0),
+            max_health: health.max,
+            ammo: stats.current_ammo,
+            max_ammo: stats.max_ammo,
+            downed,
+        })
+        .collect();
+    players.sort_by_key(|status| status.player);
+
+    let mut sprites: Vec<NetSprite> = sprites
+        .iter()
+        .filter(|(.., visibility)| visibility.is_none_or(|v| *v != Visibility::Hidden))
+        .map(|(entity, transform, sprite, id, _)| {
+            let transform = transform.compute_transform();
+            NetSprite {
+                id: entity.to_bits(),
+                player: id.map(|id| id.0),
+                position: transform.translation.truncate(),
+                z: transform.translation.z,
+                rotation: transform.rotation.to_euler(EulerRot::ZYX).0,
+                size: sprite.custom_size.unwrap_or(Vec2::ONE) * transform.scale.truncate(),
+                color: sprite.color.to_srgba().to_f32_array(),
+            }
+        })
+        .collect();
+    sprites.sort_by_key(|sprite| sprite.player.is_none());
+    sprites.truncate(MAX_SPRITES);
+
+    let mut unlocked: Vec<u32> = unlocked
+        .map(|unlocked| unlocked.0.iter().copied().collect())
+        .unwrap_or_default();
+    unlocked.sort_unstable();
+
+    let snapshot = ServerMessage::Snapshot(Snapshot {
+        tick: server.tick,
+        state: *state.get(),
+        wave: wave_state.map_or(0, |wave_state| wave_state.current_wave),
+        money: wallet.map_or(0, |wallet| wallet.money),
+        objective: objective
+            .map(|objective| objective.text.clone())
+            .unwrap_or_default(),
+        unlocked,
+        players,
+        sprites,
+        session: server.session(&settings),
+    });
+    for client in &server.clients {
+        server.socket.send(client.address, &snapshot);
+    }
+}
+
+// Clients hear about it now instead of timing out
+fn close_on_exit(server: Res<NetServer>, mut exit: EventReader<AppExit>) {
+    if exit.read().next().is_some() {
+        server.close();
+    }
+}
+
+// Starts a run once someone is connected, and goes back to waiting when
+// everyone has left or a few seconds after the run ended
+fn dedicated_flow(
+    time: Res<Time>,
+    server: Res<NetServer>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut ended_for: Local<f32>,
+) {
+    match state.get() {
+        GameState::Dashboard if !server.clients.is_empty() => {
+            info!("Starting a run for {} players", server.clients.len());
+            next_state.set(GameState::Playing);
+        }
+        GameState::Playing if server.clients.is_empty() => {
+            info!("Everyone left, waiting for players");
+            next_state.set(GameState::Dashboard);
+        }
+        GameState::Paused => next_state.set(GameState::Playing),
+        GameState::GameOver | GameState::Win => {
+            *ended_for += time.delta_seconds();
+            if *ended_for >= RESTART_SECONDS {
+                *ended_for = 0.0;
+                next_state.set(GameState::Dashboard);
+            }
+        }
+        _ => {}
+    }
+}
@@ -15,11 +15,15 @@ impl Plugin for PausePlugin {
 struct PauseUI;
 
 fn toggle_pause(
-    input: Res<ButtonInput<KeyCode>>,
+    actions: crate::plugins::controls::ActionInput,
+    player_inputs: Query<&crate::plugins::controls::PlayerInput>,
     state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
+    hosting: Option<Res<crate::plugins::net_server::NetServer>>,
 ) {
-    if input.just_pressed(KeyCode::KeyP) {
+    // Goes through PlayerInput so replays pause where the recording did.
+    // Any player can pause for everyone.
+    if player_inputs.iter().any(|input| input.pause) {
         match state.get() {
             GameState::Playing => next_state.set(GameState::Paused),
             GameState::Paused => next_state.set(GameState::Playing),
@@ -27,11 +31,15 @@ fn toggle_pause(
         }
     }
 
-    // ESC to Main Menu
-    if input.just_pressed(KeyCode::Escape)
+    // Back (ESC) to Main Menu, or to the lobby with everyone when hosting
+    if actions.just_pressed(crate::plugins::controls::Action::Back)
         && (*state.get() == GameState::Playing || *state.get() == GameState::Paused)
     {
-        next_state.set(GameState::Dashboard);
+        next_state.set(if hosting.is_some() {
+            GameState::Lobby
+        } else {
+            GameState::Dashboard
+        });
     }
 }
 
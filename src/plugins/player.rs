@@ -1,36 +1,66 @@
-use crate::components::Projectile;
+use crate::components::{Health, Projectile, ZombieTarget};
+use crate::plugins::controls::PlayerInput;
+use crate::plugins::run_modifiers::{Mutator, RunModifiers};
+use crate::plugins::simulation::{GameplaySet, Interpolated};
+use crate::states::{GameState, RunSetup};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(crate::states::GameState::Playing), spawn_player)
+        app.add_event::<ShotFired>()
+            .init_resource::<Loadout>()
+            .add_systems(RunSetup, prepare_loadout)
+            .add_systems(OnEnter(GameState::Playing), spawn_players)
+            .add_systems(
+                FixedUpdate,
+                (
+                    (player_shoot, revive_downed)
+                        .chain()
+                        .in_set(GameplaySet::Input),
+                    player_movement.in_set(GameplaySet::Movement),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            // Facing and weapon picks follow the input every frame
             .add_systems(
                 Update,
-                (player_movement, player_aim, player_shoot, weapon_switching)
-                    .run_if(in_state(crate::states::GameState::Playing)),
+                (player_aim, weapon_switching, player_colors).run_if(in_state(GameState::Playing)),
             );
     }
 }
 
 fn weapon_switching(
-    input: Res<ButtonInput<KeyCode>>,
-    mut query: Query<&mut WeaponStats, With<Player>>,
-    global_stats: Res<crate::plugins::progression::GlobalPlayerStats>,
+    mut query: Query<(&PlayerInput, &mut WeaponStats), (With<Player>, Without<Downed>)>,
+    loadout: Res<Loadout>,
+    run_modifiers: Res<RunModifiers>,
 ) {
-    if let Ok(mut stats) = query.get_single_mut() {
-        if input.just_pressed(KeyCode::Digit1) {
-            stats.current_weapon = WeaponType::Pistol;
-            info!("Switched to Pistol");
-        }
-        if input.just_pressed(KeyCode::Digit2) && global_stats.unlocked_shotgun {
-            stats.current_weapon = WeaponType::Shotgun;
-            info!("Switched to Shotgun");
-        }
-        if input.just_pressed(KeyCode::Digit3) && global_stats.unlocked_rifle {
-            stats.current_weapon = WeaponType::Rifle;
-            info!("Switched to Rifle");
+    if run_modifiers.is_active(Mutator::ShotgunOnly) {
+        return;
+    }
+
+    for (input, mut stats) in &mut query {
+        let wanted = input.weapon.or_else(|| {
+            input
+                .cycle_weapon
+                .then(|| loadout.next_weapon(stats.current_weapon))
+        });
+        match wanted {
+            Some(WeaponType::Pistol) => {
+                stats.current_weapon = WeaponType::Pistol;
+                info!("Switched to Pistol");
+            }
+            Some(WeaponType::Shotgun) if loadout.unlocked_shotgun => {
+                stats.current_weapon = WeaponType::Shotgun;
+                info!("Switched to Shotgun");
+            }
+            Some(WeaponType::Rifle) if loadout.unlocked_rifle => {
+                stats.current_weapon = WeaponType::Rifle;
+                info!("Switched to Rifle");
+            }
+            _ => {}
         }
     }
 }
@@ -38,10 +68,54 @@ fn weapon_switching(
 #[derive(Component)]
 pub struct Player;
 
+// Local co-op tops out at one keyboard and three pads
+pub const MAX_PLAYERS: usize = 4;
+
+// Which local player this is, 0 is player 1
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PlayerId(pub usize);
+
+impl PlayerId {
+    pub fn label(&self) -> String {
+        format!("P{}", self.0 + 1)
+    }
+
+    pub fn color(&self) -> Color {
+        match self.0 {
+            0 => Color::srgb(0.3, 0.3, 1.0), // Blue
+            1 => Color::srgb(1.0, 0.6, 0.2), // Orange
+            2 => Color::srgb(0.9, 0.3, 0.9), // Magenta
+            _ => Color::srgb(0.9, 0.9, 0.9), // White
+        }
+    }
+}
+
+// Out of the fight with no health left. Zombies leave them alone, and a
+// teammate standing next to them for a few seconds gets them back up.
+#[derive(Component, Debug, Default)]
+pub struct Downed {
+    // 0..1
+    pub revive_progress: f32,
+}
+
+// Health is at zero: the player drops where they stand
+pub fn knock_down(commands: &mut Commands, player: Entity, id: PlayerId) {
+    info!("{} is down!", id.label());
+    commands
+        .entity(player)
+        .insert(Downed::default())
+        .remove::<ZombieTarget>();
+}
+
+#[derive(Event)]
+pub struct ShotFired {
+    pub weapon: WeaponType,
+}
+
 #[derive(Component)]
 pub struct Speed(pub f32);
 
-#[derive(Component, Clone, Copy, PartialEq)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum WeaponType {
     Pistol,
     Shotgun,
@@ -57,76 +131,185 @@ pub struct WeaponStats {
     pub current_weapon: WeaponType,
 }
 
-fn spawn_player(
-    mut commands: Commands,
+// Dashboard upgrades the player brings into a run. Fixed when the run starts,
+// so a replay plays with the loadout it was recorded with.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Loadout {
+    pub weapon_upgrade_level: u32,
+    pub max_ammo_level: u32,
+    pub unlocked_shotgun: bool,
+    pub unlocked_rifle: bool,
+}
+
+impl Loadout {
+    // The next unlocked weapon after this one, wrapping back to the pistol
+    pub fn next_weapon(&self, current: WeaponType) -> WeaponType {
+        let order = [WeaponType::Pistol, WeaponType::Shotgun, WeaponType::Rifle];
+        let start = order
+            .iter()
+            .position(|&weapon| weapon == current)
+            .unwrap_or(0);
+        (1..order.len())
+            .map(|step| order[(start + step) % order.len()])
+            .find(|weapon| match weapon {
+                WeaponType::Pistol => true,
+                WeaponType::Shotgun => self.unlocked_shotgun,
+                WeaponType::Rifle => self.unlocked_rifle,
+            })
+            .unwrap_or(current)
+    }
+}
+
+pub fn prepare_loadout(
+    mut loadout: ResMut<Loadout>,
     global_stats: Res<crate::plugins::progression::GlobalPlayerStats>,
+    settings: Res<crate::plugins::settings::GameSettings>,
 ) {
+    // Everyone plays the daily challenge with the same base weapons
+    if settings.daily.is_some() {
+        *loadout = Loadout::default();
+        return;
+    }
+
+    *loadout = Loadout {
+        weapon_upgrade_level: global_stats.weapon_upgrade_level,
+        max_ammo_level: global_stats.max_ammo_level,
+        unlocked_shotgun: global_stats.unlocked_shotgun,
+        unlocked_rifle: global_stats.unlocked_rifle,
+    };
+}
+
+// Players line up side by side this far apart
+const PLAYER_SPACING: f32 = 30.0;
+
+pub fn spawn_players(
+    mut commands: Commands,
+    existing: Query<(), With<Player>>,
+    loadout: Res<Loadout>,
+    settings: Res<crate::plugins::settings::GameSettings>,
+    run_modifiers: Res<RunModifiers>,
+) {
+    // Unpausing enters Playing again, keep the players we already have
+    if !existing.is_empty() {
+        return;
+    }
+
+    let count = settings.player_count();
+    for index in 0..count {
+        spawn_player(
+            &mut commands,
+            PlayerId(index),
+            start_position(index, count),
+            &loadout,
+            &settings,
+            &run_modifiers,
+        );
+    }
+}
+
+// Where player `index` of `count` starts, side by side around the middle
+pub fn start_position(index: usize, count: usize) -> Vec2 {
+    Vec2::new(
+        (index as f32 - (count.max(1) - 1) as f32 / 2.0) * PLAYER_SPACING,
+        0.0,
+    )
+}
+
+// One player with the run's loadout, also used for players joining over the network
+pub fn spawn_player(
+    commands: &mut Commands,
+    id: PlayerId,
+    position: Vec2,
+    loadout: &Loadout,
+    settings: &crate::plugins::settings::GameSettings,
+    run_modifiers: &RunModifiers,
+) -> Entity {
+    let mut starting_health = settings.modifiers().player_health;
+    if run_modifiers.is_active(Mutator::GlassCannon) {
+        starting_health *= 0.5;
+    }
+
     // Calculate fire rate based on global level
     // Base 0.5, decreases by 10% per level
     let base_rate = 0.5;
-    let upgrade_factor = 0.9f32.powi(global_stats.weapon_upgrade_level as i32);
+    let upgrade_factor = 0.9f32.powi(loadout.weapon_upgrade_level as i32);
     let final_rate = base_rate * upgrade_factor;
 
     let base_ammo = 30;
-    let max_ammo = base_ammo + (global_stats.max_ammo_level * 10);
+    let max_ammo = base_ammo + (loadout.max_ammo_level * 10);
 
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::srgb(0.3, 0.3, 1.0), // Blue Player
-                custom_size: Some(Vec2::new(10.0, 10.0)),
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: id.color(),
+                    custom_size: Some(Vec2::new(10.0, 10.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(1.0)),
                 ..default()
             },
-            transform: Transform::from_xyz(0.0, 0.0, 1.0),
-            ..default()
-        },
-        Player,
-        Speed(150.0),
-        crate::components::Health {
-            current: 100.0,
-            max: 100.0,
-        },
-        WeaponStats {
-            fire_rate: final_rate,
-            timer: Timer::from_seconds(final_rate, TimerMode::Repeating),
-            current_ammo: max_ammo,
-            max_ammo,
-            current_weapon: WeaponType::Pistol,
-        },
-    ));
+            Player,
+            id,
+            PlayerInput::default(),
+            ZombieTarget,
+            Interpolated::at(position),
+            Speed(150.0),
+            Health {
+                current: starting_health,
+                max: starting_health,
+            },
+            WeaponStats {
+                fire_rate: final_rate,
+                timer: Timer::from_seconds(final_rate, TimerMode::Repeating),
+                current_ammo: max_ammo,
+                max_ammo,
+                current_weapon: if run_modifiers.is_active(Mutator::ShotgunOnly) {
+                    WeaponType::Shotgun
+                } else {
+                    WeaponType::Pistol
+                },
+            },
+        ))
+        .id()
 }
 
+// How far a co-op player can stray from the middle of the group, so
+// everyone stays on the shared screen
+const PARTY_SPREAD: Vec2 = Vec2::new(560.0, 300.0);
+
 fn player_movement(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
     time: Res<Time>,
-    mut query: Query<(&mut Transform, &Speed), With<Player>>,
+    mut query: Query<(&mut Transform, &Speed, &PlayerInput), (With<Player>, Without<Downed>)>,
+    downed_query: Query<&Transform, (With<Player>, With<Downed>)>,
     wall_query: Query<
         (&Transform, &crate::components::Collider),
         (With<crate::components::Wall>, Without<Player>),
     >,
+    bounds: Res<crate::plugins::world::MapBounds>,
 ) {
-    let mut direction = Vec2::ZERO;
+    let positions: Vec<Vec2> = query
+        .iter()
+        .map(|(transform, ..)| transform)
+        .chain(&downed_query)
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    let party_centre =
+        (positions.len() > 1).then(|| positions.iter().sum::<Vec2>() / positions.len() as f32);
 
-    if keyboard_input.pressed(KeyCode::KeyW) {
-        direction.y += 1.0;
-    }
-    if keyboard_input.pressed(KeyCode::KeyS) {
-        direction.y -= 1.0;
-    }
-    if keyboard_input.pressed(KeyCode::KeyA) {
-        direction.x -= 1.0;
-    }
-    if keyboard_input.pressed(KeyCode::KeyD) {
-        direction.x += 1.0;
-    }
-
-    if direction.length() > 0.0 {
-        direction = direction.normalize();
-    }
-
-    for (mut transform, speed) in &mut query {
-        let proposed =
-            transform.translation + direction.extend(0.0) * speed.0 * time.delta_seconds();
+    for (mut transform, speed, input) in &mut query {
+        let mut step = input.movement * speed.0 * time.delta_seconds();
+        // Walking further out than the spread allows is blocked, walking back in isn't
+        if let Some(centre) = party_centre {
+            let offset = transform.translation.truncate() + step - centre;
+            if offset.x.abs() > PARTY_SPREAD.x && offset.x.signum() == step.x.signum() {
+                step.x = 0.0;
+            }
+            if offset.y.abs() > PARTY_SPREAD.y && offset.y.signum() == step.y.signum() {
+                step.y = 0.0;
+            }
+        }
+        let proposed = transform.translation + step.extend(0.0);
         let player_size = Vec2::new(10.0, 10.0); // 1x1 Pixel Player
 
         // Simple AABB vs AABB check
@@ -146,50 +329,78 @@ fn player_movement(
         if !collision {
             transform.translation = proposed;
         }
+
+        // Never walk off the map
+        let clamped = bounds.clamp(transform.translation.truncate(), player_size / 2.0);
+        transform.translation.x = clamped.x;
+        transform.translation.y = clamped.y;
     }
 }
 
-// System to rotate player towards mouse cursor
+// Stick aim snaps onto the closest zombie within this cone and range
+const AIM_ASSIST_ANGLE: f32 = 0.26; // About 15 degrees either side
+const AIM_ASSIST_RANGE: f32 = 400.0;
+
+// System to rotate player towards the aim (the mouse cursor or right stick
+// when playing live)
 pub fn player_aim(
-    windows: Query<&Window>,
-    camera_q: Query<(&Camera, &GlobalTransform)>,
-    mut player_q: Query<&mut Transform, With<Player>>,
+    mut player_q: Query<(&mut Transform, &PlayerInput), (With<Player>, Without<Downed>)>,
+    zombie_q: Query<&Transform, (With<crate::components::Zombie>, Without<Player>)>,
 ) {
-    let window = windows.single();
-    let (camera, camera_transform) = camera_q.single();
-
-    if let Some(world_position) = window
-        .cursor_position()
-        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
-        .map(|ray| ray.origin.truncate())
-    {
-        for mut transform in &mut player_q {
-            let player_pos = transform.translation.truncate();
-            let diff = world_position - player_pos;
-            let angle = diff.y.atan2(diff.x);
-            transform.rotation = Quat::from_rotation_z(angle);
+    for (mut transform, input) in &mut player_q {
+        let Some(aim) = input.aim else {
+            continue;
+        };
+        let mut angle = aim;
+        if input.aim_assist {
+            let player = transform.translation.truncate();
+            angle = zombie_q
+                .iter()
+                .map(|zombie| zombie.translation.truncate() - player)
+                .filter(|offset| offset.length() <= AIM_ASSIST_RANGE)
+                .map(|offset| offset.y.atan2(offset.x))
+                .map(|target| (target, angle_between(aim, target)))
+                .filter(|(_, off)| *off <= AIM_ASSIST_ANGLE)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map_or(aim, |(target, _)| target);
         }
+        transform.rotation = Quat::from_rotation_z(angle);
     }
 }
 
+// Smallest angle between two facings, in radians
+fn angle_between(a: f32, b: f32) -> f32 {
+    let diff = (b - a).rem_euclid(std::f32::consts::TAU);
+    diff.min(std::f32::consts::TAU - diff)
+}
+
 fn player_shoot(
     mut commands: Commands,
-    mouse_input: Res<ButtonInput<MouseButton>>,
-    mut query: Query<(&Transform, &mut WeaponStats), With<Player>>,
+    mut query: Query<(&Transform, &mut WeaponStats, &PlayerInput), (With<Player>, Without<Downed>)>,
     time: Res<Time>,
+    mut shot_events: EventWriter<ShotFired>,
+    run_modifiers: Res<RunModifiers>,
+    balance: Res<crate::plugins::settings::Balance>,
 ) {
-    if let Ok((transform, mut stats)) = query.get_single_mut() {
+    for (transform, mut stats, input) in &mut query {
         stats.timer.tick(time.delta());
 
-        if mouse_input.pressed(MouseButton::Left)
-            && stats.timer.finished()
-            && stats.current_ammo > 0
-        {
+        if input.fire && stats.timer.finished() && stats.current_ammo > 0 {
             let aim_dir = transform.rotation * Vec3::X;
-            let damage = match stats.current_weapon {
+            let mut damage = match stats.current_weapon {
                 WeaponType::Pistol => 10.0,
                 WeaponType::Shotgun => 25.0,
                 WeaponType::Rifle => 20.0,
+            } * balance.weapon_damage;
+            if run_modifiers.is_active(Mutator::GlassCannon) {
+                damage *= 2.0;
+            }
+
+            // Low gravity bullets drift slowly but stay around twice as long
+            let (speed, lifetime) = if run_modifiers.is_active(Mutator::LowGravityBullets) {
+                (240.0, 4.0)
+            } else {
+                (400.0, 2.0)
             };
 
             commands.spawn((
@@ -202,15 +413,19 @@ fn player_shoot(
                     transform: Transform::from_translation(transform.translation),
                     ..default()
                 },
+                Interpolated::at(transform.translation.truncate()),
                 Projectile {
-                    velocity: aim_dir.truncate() * 400.0,
-                    lifetime: Timer::from_seconds(2.0, TimerMode::Once),
+                    velocity: aim_dir.truncate() * speed,
+                    lifetime: Timer::from_seconds(lifetime, TimerMode::Once),
                     damage,
                 },
             ));
 
             stats.current_ammo -= 1;
             stats.timer.reset();
+            shot_events.send(ShotFired {
+                weapon: stats.current_weapon,
+            });
 
             // Update timer duration in case upgrade happened
             let fire_rate = stats.fire_rate;
@@ -223,3 +438,53 @@ fn player_shoot(
         }
     }
 }
+
+// How close a teammate has to stand, and for how long, to get a player up
+const REVIVE_RANGE: f32 = 40.0;
+const REVIVE_SECONDS: f32 = 3.0;
+// Share of max health a revived player comes back with
+const REVIVE_HEALTH: f32 = 0.5;
+
+fn revive_downed(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut downed_query: Query<(Entity, &PlayerId, &Transform, &mut Downed, &mut Health)>,
+    standing_query: Query<&Transform, (With<Player>, Without<Downed>)>,
+) {
+    for (entity, id, transform, mut downed, mut health) in &mut downed_query {
+        let position = transform.translation.truncate();
+        let helped = standing_query
+            .iter()
+            .any(|other| other.translation.truncate().distance(position) < REVIVE_RANGE);
+
+        // Walking away loses the progress
+        if helped {
+            downed.revive_progress += time.delta_seconds() / REVIVE_SECONDS;
+        } else {
+            downed.revive_progress = 0.0;
+        }
+
+        if downed.revive_progress >= 1.0 {
+            health.current = health.max * REVIVE_HEALTH;
+            commands
+                .entity(entity)
+                .remove::<Downed>()
+                .insert(ZombieTarget);
+            info!("{} is back up!", id.label());
+        }
+    }
+}
+
+// Downed players fade so teammates can spot who needs help
+fn player_colors(mut query: Query<(&PlayerId, &mut Sprite, Has<Downed>), With<Player>>) {
+    for (id, mut sprite, downed) in &mut query {
+        let color = if downed {
+            id.color().with_alpha(0.35)
+        } else {
+            id.color()
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
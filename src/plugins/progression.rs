@@ -1,6 +1,9 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Resource, Default)]
+// This is the player's profile, it is saved to disk whenever it changes
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GlobalPlayerStats {
     pub total_money: u32,
     pub level: u32,
@@ -8,12 +11,27 @@ pub struct GlobalPlayerStats {
     pub max_ammo_level: u32,
     pub unlocked_shotgun: bool,
     pub unlocked_rifle: bool,
+    pub best_endless_wave: u32,
 }
 
+const PROFILE_SAVE: &str = "profile";
+
 pub struct ProgressionPlugin;
 
 impl Plugin for ProgressionPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<GlobalPlayerStats>();
+        let stats: GlobalPlayerStats = crate::storage::load(PROFILE_SAVE).unwrap_or_default();
+        app.insert_resource(stats).add_systems(
+            Update,
+            save_profile.run_if(resource_changed::<GlobalPlayerStats>),
+        );
+    }
+}
+
+fn save_profile(stats: Res<GlobalPlayerStats>) {
+    // Skip the write triggered by inserting the resource at startup
+    if stats.is_added() {
+        return;
     }
+    crate::storage::save(PROFILE_SAVE, &*stats);
 }
@@ -0,0 +1,497 @@
+use crate::plugins::controls::PlayerInput;
+use crate::plugins::daily_challenge::DailyChallenge;
+use crate::plugins::game_mode::GameMode;
+use crate::plugins::player::{Loadout, PlayerId};
+use crate::plugins::rng::GameRng;
+use crate::plugins::run_modifiers::{Mutator, RunModifiers};
+use crate::plugins::settings::{CustomDifficulty, Difficulty, GameSettings};
+use crate::plugins::ui::spawn_button;
+use crate::states::{DashboardScreen, GameState, RunSetup, RunTeardown};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let library: ReplayLibrary = crate::storage::load(LIBRARY_SAVE).unwrap_or_default();
+
+        app.insert_resource(library)
+            .init_resource::<ReplayState>()
+            .add_systems(
+                RunSetup,
+                start_run
+                    .after(crate::plugins::player::prepare_loadout)
+                    .after(crate::plugins::rng::seed_run)
+                    .after(crate::plugins::run_modifiers::activate_run_modifiers)
+                    .after(crate::plugins::daily_challenge::apply_daily_game_mode),
+            )
+            // A run abandoned with ESC never reaches GameOver, so whatever was
+            // recorded is saved on the way out, before the wave count is reset
+            .add_systems(
+                RunTeardown,
+                (finish_recording, stop_replay)
+                    .chain()
+                    .before(crate::plugins::wave::reset_waves),
+            )
+            .add_systems(OnEnter(GameState::GameOver), finish_recording)
+            .add_systems(OnEnter(GameState::Win), finish_recording)
+            .add_systems(
+                PreUpdate,
+                feed_replay_input.after(crate::plugins::controls::read_player_input),
+            )
+            .add_systems(
+                Last,
+                step_replay
+                    .run_if(in_state(GameState::Playing).or_else(in_state(GameState::Paused))),
+            )
+            .add_systems(OnEnter(DashboardScreen::Replays), setup_replays_menu)
+            .add_systems(OnExit(DashboardScreen::Replays), cleanup_replays_menu)
+            .add_systems(
+                Update,
+                (replays_menu_interactions, replays_menu_visuals)
+                    .run_if(in_state(DashboardScreen::Replays)),
+            )
+            .add_systems(
+                Update,
+                save_library.run_if(resource_changed::<ReplayLibrary>),
+            );
+    }
+}
+
+const LIBRARY_SAVE: &str = "replays";
+// Finished runs kept on disk, the oldest is overwritten first
+const REPLAY_SLOTS: usize = 5;
+
+// Everything needed to play a run again: how it was set up, then what the
+// player did on every frame and how much game time that frame covered.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub difficulty: Option<Difficulty>,
+    pub game_mode: GameMode,
+    pub custom: CustomDifficulty,
+    // Set when the run was a daily challenge
+    pub daily_day: Option<u64>,
+    pub mutators: BTreeSet<Mutator>,
+    pub loadout: Loadout,
+    // Co-op players on top of the first one
+    #[serde(default)]
+    pub extra_players: usize,
+    pub frames: Vec<ReplayFrame>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    // Game time in nanoseconds, kept exact so playback steps the same amounts
+    pub dt: u32,
+    pub input: PlayerInput,
+    // Players 2 and up, left out of solo recordings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub others: Vec<PlayerInput>,
+}
+
+impl ReplayFrame {
+    fn duration(&self) -> Duration {
+        Duration::from_nanos(u64::from(self.dt))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ReplayMode {
+    #[default]
+    Off,
+    Recording,
+    Playback,
+}
+
+#[derive(Resource, Default)]
+pub struct ReplayState {
+    mode: ReplayMode,
+    replay: Replay,
+    // Next frame to play back
+    cursor: usize,
+    // The player's own settings, put back once the replay is over
+    stashed_settings: Option<GameSettings>,
+}
+
+impl ReplayState {
+    pub fn is_playing_back(&self) -> bool {
+        self.mode == ReplayMode::Playback
+    }
+}
+
+// Watching a replay must not pay out money, records or leaderboard entries
+pub fn watching_replay(state: Res<ReplayState>) -> bool {
+    state.is_playing_back()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaySummary {
+    pub file: String,
+    pub seed: u64,
+    pub difficulty: Option<Difficulty>,
+    pub game_mode: GameMode,
+    pub daily: Option<String>,
+    pub won: bool,
+    // Abandoned from the pause menu before it was over
+    #[serde(default)]
+    pub quit: bool,
+    pub wave: u32,
+    pub seconds: f32,
+}
+
+impl ReplaySummary {
+    fn describe(&self) -> String {
+        let run = match &self.daily {
+            Some(date) => format!("Daily {}", date),
+            None => format!(
+                "{:?} {}",
+                self.difficulty.unwrap_or_default(),
+                self.game_mode.label()
+            ),
+        };
+        let outcome = if self.won {
+            "Won"
+        } else if self.quit {
+            "Quit"
+        } else {
+            "Died"
+        };
+        format!(
+            "{} | {} on wave {} after {:.0}s | Seed {}",
+            run, outcome, self.wave, self.seconds, self.seed
+        )
+    }
+}
+
+// Saved replays, newest first. Each replay lives in its own file.
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplayLibrary {
+    pub entries: Vec<ReplaySummary>,
+    next_slot: usize,
+}
+
+impl ReplayLibrary {
+    fn add(&mut self, replay: &Replay, mut summary: ReplaySummary) {
+        summary.file = format!("replay_{}", self.next_slot);
+        self.next_slot = (self.next_slot + 1) % REPLAY_SLOTS;
+
+        crate::storage::save(&summary.file, replay);
+        self.entries.retain(|entry| entry.file != summary.file);
+        self.entries.insert(0, summary);
+    }
+}
+
+// Every run is recorded. When a replay was picked instead, its setup
+// overrides whatever the dashboard rolled for the run.
+fn start_run(
+    mut replay_state: ResMut<ReplayState>,
+    settings: Res<GameSettings>,
+    mut rng: ResMut<GameRng>,
+    mut modifiers: ResMut<RunModifiers>,
+    mut loadout: ResMut<Loadout>,
+    hosting: Option<Res<crate::plugins::net_server::NetServer>>,
+) {
+    if replay_state.is_playing_back() {
+        let replay = &replay_state.replay;
+        *rng = GameRng::from_seed(replay.seed);
+        modifiers.active = replay.mutators.clone();
+        *loadout = replay.loadout;
+        info!("Watching Replay ({} frames)", replay.frames.len());
+        return;
+    }
+    // Online players come and go mid-run, which a replay can't play back
+    if hosting.is_some() {
+        return;
+    }
+
+    replay_state.mode = ReplayMode::Recording;
+    replay_state.replay = Replay {
+        seed: rng.seed(),
+        difficulty: settings.difficulty,
+        game_mode: settings.game_mode,
+        custom: settings.custom,
+        daily_day: settings.daily.as_ref().map(|challenge| challenge.day),
+        mutators: modifiers.active.clone(),
+        loadout: *loadout,
+        extra_players: settings.extra_players,
+        frames: Vec::new(),
+    };
+}
+
+// Overrides the live input of every player with the recorded one
+fn feed_replay_input(
+    replay_state: Res<ReplayState>,
+    mut players: Query<(&PlayerId, &mut PlayerInput)>,
+) {
+    if !replay_state.is_playing_back() {
+        return;
+    }
+
+    let frame = replay_state.replay.frames.get(replay_state.cursor);
+    for (id, mut input) in &mut players {
+        *input = frame
+            .and_then(|frame| match id.0 {
+                0 => Some(frame.input),
+                index => frame.others.get(index - 1).copied(),
+            })
+            .unwrap_or_default();
+    }
+}
+
+// Runs last in the frame: records what this frame did, or lines up the
+// recorded frame time for the next one.
+fn step_replay(
+    mut replay_state: ResMut<ReplayState>,
+    players: Query<(&PlayerId, &PlayerInput)>,
+    time: Res<Time<Virtual>>,
+    mut strategy: ResMut<TimeUpdateStrategy>,
+) {
+    match replay_state.mode {
+        ReplayMode::Off => {}
+        ReplayMode::Recording => {
+            let mut inputs: Vec<_> = players.iter().collect();
+            inputs.sort_by_key(|(id, _)| **id);
+            let mut inputs = inputs.into_iter().map(|(_, input)| *input);
+            let frame = ReplayFrame {
+                dt: time.delta().as_nanos() as u32,
+                input: inputs.next().unwrap_or_default(),
+                others: inputs.collect(),
+            };
+            replay_state.replay.frames.push(frame);
+        }
+        ReplayMode::Playback => {
+            replay_state.cursor += 1;
+            let cursor = replay_state.cursor;
+            *strategy = match replay_state.replay.frames.get(cursor) {
+                Some(frame) => TimeUpdateStrategy::ManualDuration(frame.duration()),
+                None => {
+                    if cursor == replay_state.replay.frames.len() {
+                        info!("Replay Finished");
+                    }
+                    TimeUpdateStrategy::Automatic
+                }
+            };
+        }
+    }
+}
+
+fn finish_recording(
+    state: Res<State<GameState>>,
+    mut replay_state: ResMut<ReplayState>,
+    wave_state: Res<crate::plugins::wave::WaveState>,
+    mode_state: Res<crate::plugins::game_mode::ModeState>,
+    mut library: ResMut<ReplayLibrary>,
+) {
+    if replay_state.mode != ReplayMode::Recording {
+        return;
+    }
+    replay_state.mode = ReplayMode::Off;
+
+    let replay = std::mem::take(&mut replay_state.replay);
+    let summary = ReplaySummary {
+        file: String::new(),
+        seed: replay.seed,
+        difficulty: replay.difficulty,
+        game_mode: replay.game_mode,
+        daily: replay
+            .daily_day
+            .map(|day| DailyChallenge::for_day(day).date),
+        won: *state.get() == GameState::Win,
+        // Only a run that ended on its own reaches GameOver or Win
+        quit: !matches!(state.get(), GameState::GameOver | GameState::Win),
+        wave: wave_state.current_wave,
+        seconds: mode_state.elapsed,
+    };
+    info!("Saved Replay: {}", summary.describe());
+    library.add(&replay, summary);
+}
+
+// Back on the dashboard nothing is recorded any more and a finished replay
+// hands the settings back.
+pub fn stop_replay(
+    mut replay_state: ResMut<ReplayState>,
+    mut settings: ResMut<GameSettings>,
+    mut strategy: ResMut<TimeUpdateStrategy>,
+) {
+    if let Some(stashed) = replay_state.stashed_settings.take() {
+        *settings = stashed;
+    }
+    // Only playback takes over the clock, a headless app keeps its fixed steps
+    if replay_state.is_playing_back() {
+        *strategy = TimeUpdateStrategy::Automatic;
+    }
+    *replay_state = ReplayState::default();
+}
+
+fn save_library(library: Res<ReplayLibrary>) {
+    // Skip the write triggered by inserting the resource at startup
+    if library.is_added() {
+        return;
+    }
+    crate::storage::save(LIBRARY_SAVE, &*library);
+}
+
+#[derive(Component)]
+struct ReplaysUI;
+
+#[derive(Component)]
+enum ReplayButton {
+    Watch(usize),
+    Back,
+}
+
+fn setup_replays_menu(mut commands: Commands, library: Res<ReplayLibrary>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 1.0).into(), // Dark Grey Background
+                ..default()
+            },
+            ReplaysUI,
+        ))
+        .with_children(|parent| {
+            // Title
+            parent.spawn(TextBundle::from_section(
+                "REPLAYS",
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::srgb(0.0, 1.0, 0.0), // Green Title
+                    ..default()
+                },
+            ));
+
+            if library.entries.is_empty() {
+                parent.spawn(TextBundle::from_section(
+                    "No replays yet. Finished runs are saved here.",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ));
+            }
+
+            // [Watch] summary
+            for (index, entry) in library.entries.iter().enumerate() {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(10.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        spawn_button(row, "Watch", 100.0, ReplayButton::Watch(index));
+                        row.spawn(TextBundle::from_section(
+                            entry.describe(),
+                            TextStyle {
+                                font_size: 18.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(10.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    spawn_button(row, "Back", 200.0, ReplayButton::Back);
+                });
+        });
+}
+
+fn cleanup_replays_menu(mut commands: Commands, query: Query<Entity, With<ReplaysUI>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn replays_menu_interactions(
+    interaction_query: Query<(&Interaction, &ReplayButton), (Changed<Interaction>, With<Button>)>,
+    library: Res<ReplayLibrary>,
+    mut replay_state: ResMut<ReplayState>,
+    mut settings: ResMut<GameSettings>,
+    mut strategy: ResMut<TimeUpdateStrategy>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_screen: ResMut<NextState<DashboardScreen>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            ReplayButton::Watch(index) => {
+                let entry = &library.entries[*index];
+                let Some(replay) = crate::storage::load::<Replay>(&entry.file) else {
+                    warn!("Replay {} could not be loaded", entry.file);
+                    continue;
+                };
+                let Some(first_frame) = replay.frames.first() else {
+                    warn!("Replay {} is empty", entry.file);
+                    continue;
+                };
+
+                // The run plays with the recorded setup, only the camera
+                // effects stay the player's own
+                let stashed = settings.clone();
+                *settings = GameSettings {
+                    difficulty: replay.difficulty,
+                    camera_effects: stashed.camera_effects,
+                    game_mode: replay.game_mode,
+                    custom: replay.custom,
+                    daily: replay.daily_day.map(DailyChallenge::for_day),
+                    seed: Some(replay.seed),
+                    extra_players: replay.extra_players,
+                    open_section: None,
+                };
+                // The first recorded frame is the one that enters Playing
+                *strategy = TimeUpdateStrategy::ManualDuration(first_frame.duration());
+                *replay_state = ReplayState {
+                    mode: ReplayMode::Playback,
+                    replay,
+                    cursor: 0,
+                    stashed_settings: Some(stashed),
+                };
+                next_state.set(GameState::Playing);
+            }
+            ReplayButton::Back => next_screen.set(DashboardScreen::Main),
+        }
+    }
+}
+
+fn replays_menu_visuals(
+    mut query: Query<(&Interaction, &mut BackgroundColor), (With<Button>, With<ReplayButton>)>,
+) {
+    for (interaction, mut color) in &mut query {
+        *color = match interaction {
+            Interaction::Hovered => Color::srgb(0.3, 0.3, 0.3).into(),
+            _ => Color::srgb(0.2, 0.2, 0.2).into(),
+        };
+    }
+}
@@ -0,0 +1,75 @@
+use crate::states::RunSetup;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameRng::from_seed(rand::rng().random()))
+            .add_systems(RunSetup, seed_run);
+    }
+}
+
+// Every subsystem that rolls dice gets its own stream, so extra rolls in one
+// (say, a new spawn retry) don't shift the results of another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RngStream {
+    Spawns,
+}
+
+impl RngStream {
+    const ALL: [RngStream; 1] = [RngStream::Spawns];
+}
+
+// All randomness in a run comes from here. The same seed replays the same run.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    streams: Vec<StdRng>,
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: RngStream::ALL
+                .iter()
+                .map(|&stream| StdRng::seed_from_u64(stream_seed(seed, stream)))
+                .collect(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut StdRng {
+        &mut self.streams[stream as usize]
+    }
+}
+
+fn stream_seed(seed: u64, stream: RngStream) -> u64 {
+    splitmix64(seed ^ splitmix64(stream as u64))
+}
+
+// Spreads nearby inputs (consecutive days, stream indices) far apart
+pub fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// The daily challenge fixes the seed, otherwise the one typed on the dashboard
+// is used, and failing that a fresh random one
+pub fn seed_run(mut rng: ResMut<GameRng>, settings: Res<crate::plugins::settings::GameSettings>) {
+    let seed = match (&settings.daily, settings.seed) {
+        (Some(challenge), _) => challenge.seed,
+        (None, Some(seed)) => seed,
+        (None, None) => rand::rng().random(),
+    };
+    *rng = GameRng::from_seed(seed);
+    info!("Run Seed: {}", seed);
+}
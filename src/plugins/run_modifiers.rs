@@ -0,0 +1,135 @@
+use crate::plugins::enemy::ZombieScaling;
+use crate::states::RunSetup;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+pub struct RunModifiersPlugin;
+
+impl Plugin for RunModifiersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunModifiers>()
+            .add_systems(RunSetup, activate_run_modifiers);
+    }
+}
+
+// Optional rules that change how a run plays. Picked on the dashboard, or
+// rolled by the daily challenge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Mutator {
+    // Starts with the shotgun and can't switch away from it
+    ShotgunOnly,
+    DoubleSpeedZombies,
+    // Zombies have 50% more health
+    ToughZombies,
+    // The in-run shop stays closed between waves
+    NoShop,
+    // Slow, floaty bullets that travel twice as long
+    LowGravityBullets,
+    // Kills heal the player
+    Vampirism,
+    // Half health, double damage
+    GlassCannon,
+    // Zombies fade out of sight away from the player
+    Fog,
+    // Zombies blow up when they die, hurting the player if close
+    ExplosiveZombies,
+}
+
+impl Mutator {
+    pub const ALL: [Mutator; 9] = [
+        Mutator::ShotgunOnly,
+        Mutator::DoubleSpeedZombies,
+        Mutator::ToughZombies,
+        Mutator::NoShop,
+        Mutator::LowGravityBullets,
+        Mutator::Vampirism,
+        Mutator::GlassCannon,
+        Mutator::Fog,
+        Mutator::ExplosiveZombies,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Mutator::ShotgunOnly => "Shotgun Only",
+            Mutator::DoubleSpeedZombies => "Double Speed Zombies",
+            Mutator::ToughZombies => "Tough Zombies",
+            Mutator::NoShop => "No Shop",
+            Mutator::LowGravityBullets => "Low Gravity Bullets",
+            Mutator::Vampirism => "Vampirism",
+            Mutator::GlassCannon => "Glass Cannon",
+            Mutator::Fog => "Fog",
+            Mutator::ExplosiveZombies => "Explosive Zombies",
+        }
+    }
+
+    // Extra reward share for playing with this on. Vampirism makes runs easier, so it costs.
+    pub fn reward_bonus(&self) -> f32 {
+        match self {
+            Mutator::ShotgunOnly => 0.2,
+            Mutator::DoubleSpeedZombies => 0.5,
+            Mutator::ToughZombies => 0.3,
+            Mutator::NoShop => 0.3,
+            Mutator::LowGravityBullets => 0.1,
+            Mutator::Vampirism => -0.2,
+            Mutator::GlassCannon => 0.4,
+            Mutator::Fog => 0.3,
+            Mutator::ExplosiveZombies => 0.25,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct RunModifiers {
+    // What the player has toggled on the dashboard
+    pub selected: BTreeSet<Mutator>,
+    // What the current run plays with, fixed when the run starts
+    pub active: BTreeSet<Mutator>,
+}
+
+impl RunModifiers {
+    pub fn is_active(&self, mutator: Mutator) -> bool {
+        self.active.contains(&mutator)
+    }
+
+    pub fn toggle(&mut self, mutator: Mutator) {
+        if !self.selected.remove(&mutator) {
+            self.selected.insert(mutator);
+        }
+    }
+
+    // Bonuses stack multiplicatively, so harder combinations pay out more
+    pub fn reward_multiplier(mutators: &BTreeSet<Mutator>) -> f32 {
+        mutators
+            .iter()
+            .map(|mutator| 1.0 + mutator.reward_bonus())
+            .product()
+    }
+
+    // Zombie stat changes on top of the wave's own scaling
+    pub fn zombie_scaling(&self, mut scaling: ZombieScaling) -> ZombieScaling {
+        if self.is_active(Mutator::DoubleSpeedZombies) {
+            scaling.speed *= 2.0;
+        }
+        if self.is_active(Mutator::ToughZombies) {
+            scaling.health *= 1.5;
+        }
+        scaling.reward *= Self::reward_multiplier(&self.active);
+        scaling
+    }
+}
+
+pub fn mutator_active(mutator: Mutator) -> impl Fn(Res<RunModifiers>) -> bool {
+    move |modifiers| modifiers.is_active(mutator)
+}
+
+// The daily challenge brings its own mutators and ignores the dashboard picks
+pub fn activate_run_modifiers(
+    mut modifiers: ResMut<RunModifiers>,
+    settings: Res<crate::plugins::settings::GameSettings>,
+) {
+    modifiers.active = match &settings.daily {
+        Some(challenge) => challenge.modifiers.iter().copied().collect(),
+        None => modifiers.selected.clone(),
+    };
+}
@@ -1,32 +1,209 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Difficulty {
     Easy,
     #[default]
     Medium,
     Hard,
+    // No final wave, keeps scaling until the player dies
+    Endless,
+    // Generated waves tuned by `CustomDifficulty`
+    Custom,
 }
 
-impl Difficulty {
-    pub fn max_waves(&self) -> u32 {
-        match self {
-            Difficulty::Easy => 1,
-            Difficulty::Medium => 2,
-            Difficulty::Hard => 3,
+// Knobs for the Custom difficulty. Everything except `waves` and
+// `player_health` is a multiplier on top of the generated waves.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomDifficulty {
+    pub waves: u32,
+    pub zombie_health: f32,
+    pub zombie_speed: f32,
+    pub zombie_damage: f32,
+    // Higher means zombies spawn more often
+    pub spawn_rate: f32,
+    pub reward: f32,
+    pub player_health: f32,
+}
+
+impl CustomDifficulty {
+    // What the built-in difficulties play with
+    pub const STANDARD: Self = Self {
+        waves: 5,
+        zombie_health: 1.0,
+        zombie_speed: 1.0,
+        zombie_damage: 1.0,
+        spawn_rate: 1.0,
+        reward: 1.0,
+        player_health: 100.0,
+    };
+
+    // Limits the Custom Difficulty screen keeps every knob within
+    pub const MIN_WAVES: u32 = 1;
+    pub const MAX_WAVES: u32 = 50;
+    pub const MIN_MULTIPLIER: f32 = 0.1;
+    pub const MAX_MULTIPLIER: f32 = 5.0;
+    pub const MIN_PLAYER_HEALTH: f32 = 25.0;
+    pub const MAX_PLAYER_HEALTH: f32 = 500.0;
+
+    // Saved presets and replays come from disk, so they are pulled back into
+    // the limits before use. A 0 spawn rate or 0 waves would stall the run.
+    pub fn clamped(self) -> Self {
+        let multiplier = |value: f32| {
+            if value.is_nan() {
+                1.0
+            } else {
+                value.clamp(Self::MIN_MULTIPLIER, Self::MAX_MULTIPLIER)
+            }
+        };
+        Self {
+            waves: self.waves.clamp(Self::MIN_WAVES, Self::MAX_WAVES),
+            zombie_health: multiplier(self.zombie_health),
+            zombie_speed: multiplier(self.zombie_speed),
+            zombie_damage: multiplier(self.zombie_damage),
+            spawn_rate: multiplier(self.spawn_rate),
+            reward: multiplier(self.reward),
+            player_health: if self.player_health.is_nan() {
+                Self::STANDARD.player_health
+            } else {
+                self.player_health
+                    .clamp(Self::MIN_PLAYER_HEALTH, Self::MAX_PLAYER_HEALTH)
+            },
         }
     }
 }
 
-#[derive(Resource, Default)]
+impl Default for CustomDifficulty {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraEffectsSettings {
+    // 0.0 disables screen shake entirely
+    pub shake_intensity: f32,
+    pub hit_stop: bool,
+    pub zoom_pulse: bool,
+}
+
+impl CameraEffectsSettings {
+    pub const FULL: Self = Self {
+        shake_intensity: 1.0,
+        hit_stop: true,
+        zoom_pulse: true,
+    };
+    pub const REDUCED: Self = Self {
+        shake_intensity: 0.4,
+        hit_stop: false,
+        zoom_pulse: false,
+    };
+    pub const OFF: Self = Self {
+        shake_intensity: 0.0,
+        hit_stop: false,
+        zoom_pulse: false,
+    };
+
+    // Dashboard toggle cycles Full -> Reduced -> Off -> Full
+    pub fn next_preset(&self) -> Self {
+        if *self == Self::FULL {
+            Self::REDUCED
+        } else if *self == Self::REDUCED {
+            Self::OFF
+        } else {
+            Self::FULL
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        if *self == Self::FULL {
+            "Full"
+        } else if *self == Self::REDUCED {
+            "Reduced"
+        } else if *self == Self::OFF {
+            "Off"
+        } else {
+            "Custom"
+        }
+    }
+}
+
+impl Default for CameraEffectsSettings {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+#[derive(Resource, Default, Clone)]
 pub struct GameSettings {
     pub difficulty: Option<Difficulty>,
+    pub camera_effects: CameraEffectsSettings,
+    pub game_mode: crate::plugins::game_mode::GameMode,
+    pub custom: CustomDifficulty,
+    // Set when the run is today's daily challenge
+    pub daily: Option<crate::plugins::daily_challenge::DailyChallenge>,
+    // Typed on the dashboard to replay a run, None picks a random seed
+    pub seed: Option<u64>,
+    // Local co-op players on top of the first one
+    pub extra_players: usize,
+    // Wing the host of an online session opens from the start
+    pub open_section: Option<u32>,
+}
+
+impl GameSettings {
+    pub fn player_count(&self) -> usize {
+        (1 + self.extra_players).min(crate::plugins::player::MAX_PLAYERS)
+    }
+
+    // The custom knobs only apply when Custom is picked
+    pub fn modifiers(&self) -> CustomDifficulty {
+        if self.difficulty == Some(Difficulty::Custom) {
+            self.custom.clamped()
+        } else {
+            CustomDifficulty::STANDARD
+        }
+    }
+}
+
+// Tuning knobs the balance simulator sweeps. The game always plays with the
+// defaults, which leave everything as designed.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Balance {
+    // Multiplier on every weapon's bullet damage
+    pub weapon_damage: f32,
+    // Extra zombie health per wave after the first, 0.1 means +10% each wave
+    pub zombie_health_per_wave: f32,
+    // Multiplier on in-run shop prices
+    pub upgrade_cost: f32,
+}
+
+impl Balance {
+    pub fn zombie_health(&self, wave: u32) -> f32 {
+        1.0 + self.zombie_health_per_wave * wave.saturating_sub(1) as f32
+    }
+
+    pub fn cost(&self, base: u32) -> u32 {
+        (base as f32 * self.upgrade_cost).round() as u32
+    }
+}
+
+impl Default for Balance {
+    fn default() -> Self {
+        Self {
+            weapon_damage: 1.0,
+            zombie_health_per_wave: 0.0,
+            upgrade_cost: 1.0,
+        }
+    }
 }
 
 pub struct SettingsPlugin;
 
 impl Plugin for SettingsPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<GameSettings>();
+        app.init_resource::<GameSettings>()
+            .init_resource::<Balance>();
     }
 }
@@ -8,46 +8,96 @@ pub struct Wallet {
 #[derive(Resource)]
 pub struct WeaponUpgradeCost {
     pub fire_rate_cost: u32,
+    pub medkit_cost: u32,
 }
 
 impl Default for WeaponUpgradeCost {
     fn default() -> Self {
-        Self { fire_rate_cost: 10 }
+        Self {
+            fire_rate_cost: 10,
+            medkit_cost: 40,
+        }
     }
 }
 
+// How much the fire rate upgrade goes up after each purchase
+const FIRE_RATE_COST_STEP: u32 = 50;
+
 pub struct ShopPlugin;
 
 impl Plugin for ShopPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Wallet>()
            .init_resource::<WeaponUpgradeCost>()
-           .add_systems(Update, shop_input.run_if(in_state(crate::states::GameState::Playing)));
+           .add_systems(crate::states::RunSetup, reset_shop)
+           // Fire rate can be bought at any time, medkits only between waves
+           .add_systems(Update, shop_input.run_if(in_state(crate::states::GameState::Playing).and_then(shop_open)));
     }
 }
 
+// Every run starts broke and at base prices, whatever the last one left behind
+fn reset_shop(mut wallet: ResMut<Wallet>, mut cost: ResMut<WeaponUpgradeCost>, balance: Res<crate::plugins::settings::Balance>) {
+    wallet.money = 0;
+    let base = WeaponUpgradeCost::default();
+    *cost = WeaponUpgradeCost {
+        fire_rate_cost: balance.cost(base.fire_rate_cost),
+        medkit_cost: balance.cost(base.medkit_cost),
+    };
+}
+
+// The No Shop mutator closes it for the whole run
+fn shop_open(run_modifiers: Res<crate::plugins::run_modifiers::RunModifiers>) -> bool {
+    !run_modifiers.is_active(crate::plugins::run_modifiers::Mutator::NoShop)
+}
+
+// Each player shops for themselves out of the shared wallet
 fn shop_input(
     _commands: Commands,
-    input: Res<ButtonInput<KeyCode>>,
     mut wallet: ResMut<Wallet>,
     mut cost: ResMut<WeaponUpgradeCost>,
-    mut player_query: Query<&mut crate::plugins::player::WeaponStats>, // We need to add WeaponStats to player
+    balance: Res<crate::plugins::settings::Balance>,
+    phase: Res<State<crate::states::WavePhase>>,
+    mut player_query: Query<
+        (
+            &crate::plugins::controls::PlayerInput,
+            &mut crate::plugins::player::WeaponStats,
+            &mut crate::components::Health,
+        ),
+        Without<crate::plugins::player::Downed>,
+    >,
 ) {
-    if input.just_pressed(KeyCode::KeyB) {
-        if wallet.money >= cost.fire_rate_cost {
-             // Upgrade Logic
-             if let Ok(mut stats) = player_query.get_single_mut() {
-                 wallet.money -= cost.fire_rate_cost;
-                 stats.fire_rate *= 0.8; // Decrease cooldown
-                 // Apply new duration immediately
-                 let new_rate = stats.fire_rate;
-                 stats.timer.set_duration(std::time::Duration::from_secs_f32(new_rate));
-                 
-                 cost.fire_rate_cost += 50; // Increase price
-                 info!("Weapon Upgraded! New Fire Rate: {:.2}", stats.fire_rate);
-             }
-        } else {
-            info!("Not enough money! Need ${}", cost.fire_rate_cost);
+    for (input, mut stats, mut health) in &mut player_query {
+        if input.buy_upgrade {
+            if wallet.money >= cost.fire_rate_cost {
+                // Upgrade Logic
+                wallet.money -= cost.fire_rate_cost;
+                stats.fire_rate *= 0.8; // Decrease cooldown
+                // Apply new duration immediately
+                let new_rate = stats.fire_rate;
+                stats
+                    .timer
+                    .set_duration(std::time::Duration::from_secs_f32(new_rate));
+
+                cost.fire_rate_cost += balance.cost(FIRE_RATE_COST_STEP); // Increase price
+                info!("Weapon Upgraded! New Fire Rate: {:.2}", stats.fire_rate);
+            } else {
+                info!("Not enough money! Need ${}", cost.fire_rate_cost);
+            }
+        }
+
+        if input.buy_medkit && *phase.get() == crate::states::WavePhase::Intermission {
+            if wallet.money < cost.medkit_cost {
+                info!("Not enough money! Need ${}", cost.medkit_cost);
+            } else if health.current >= health.max {
+                info!("Already at full health!");
+            } else {
+                wallet.money -= cost.medkit_cost;
+                health.current = (health.current + 50.0).min(health.max);
+                info!(
+                    "Medkit Used! Health: {:.0}/{:.0}",
+                    health.current, health.max
+                );
+            }
         }
     }
 }
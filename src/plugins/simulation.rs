@@ -0,0 +1,101 @@
+use bevy::app::RunFixedMainLoop;
+use bevy::prelude::*;
+use bevy::time::run_fixed_main_schedule;
+
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Time::<Fixed>::from_hz(TICK_RATE))
+            .add_systems(
+                RunFixedMainLoop,
+                (
+                    restore_simulated_translation.before(run_fixed_main_schedule),
+                    interpolate_translation.after(run_fixed_main_schedule),
+                ),
+            )
+            .add_systems(FixedFirst, remember_previous_translation)
+            .configure_sets(
+                FixedUpdate,
+                (
+                    GameplaySet::Input,
+                    GameplaySet::Movement,
+                    GameplaySet::Ai,
+                    GameplaySet::Combat,
+                    GameplaySet::Waves,
+                    GameplaySet::Spawning,
+                    GameplaySet::Rules,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// Gameplay (movement, combat, waves, zombie AI) runs in FixedUpdate at this
+// rate, so it plays out the same on slow and fast machines
+pub const TICK_RATE: f64 = 60.0;
+
+// The steps of a gameplay tick, always run in this order. Systems in
+// different steps touch the same transforms, health and spawns, so leaving
+// the order to the executor would let a seed or a replay play out
+// differently from one run to the next.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameplaySet {
+    // Shooting and reviving from this tick's input
+    Input,
+    Movement,
+    // Zombies chase wherever the players just moved to
+    Ai,
+    Combat,
+    Waves,
+    // Telegraphs hatching into zombies
+    Spawning,
+    // Game mode win and loss checks, once everything else has settled
+    Rules,
+}
+
+// Sprites moved by the simulation. Gameplay sees the simulated position in
+// Transform; for the rest of the frame it is blended between the last two
+// ticks so movement stays smooth when the frame rate doesn't match.
+#[derive(Component)]
+pub struct Interpolated {
+    previous: Vec2,
+    current: Vec2,
+}
+
+impl Interpolated {
+    pub fn at(position: Vec2) -> Self {
+        Self {
+            previous: position,
+            current: position,
+        }
+    }
+}
+
+// Undo last frame's blending before gameplay runs. Only x/y are touched,
+// z belongs to the y-sort.
+fn restore_simulated_translation(mut query: Query<(&mut Transform, &Interpolated)>) {
+    for (mut transform, interpolated) in &mut query {
+        transform.translation.x = interpolated.current.x;
+        transform.translation.y = interpolated.current.y;
+    }
+}
+
+fn remember_previous_translation(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in &mut query {
+        interpolated.previous = transform.translation.truncate();
+    }
+}
+
+fn interpolate_translation(
+    time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &mut Interpolated)>,
+) {
+    let alpha = time.overstep_fraction();
+    for (mut transform, mut interpolated) in &mut query {
+        interpolated.current = transform.translation.truncate();
+        let blended = interpolated.previous.lerp(interpolated.current, alpha);
+        transform.translation.x = blended.x;
+        transform.translation.y = blended.y;
+    }
+}
@@ -0,0 +1,252 @@
+use crate::components::{Collider, SpawnPoint, Wall};
+use crate::plugins::enemy::{ZombieArchetype, ZombieScaling, spawn_zombie};
+use crate::plugins::player::Player;
+use crate::plugins::rng::{GameRng, RngStream};
+use crate::plugins::simulation::GameplaySet;
+use crate::plugins::world::{MapBounds, UnlockedSections, section_at};
+use crate::systems::camera::VIEW_SIZE;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+
+pub struct SpawningPlugin;
+
+impl Plugin for SpawningPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            hatch_telegraphs
+                .in_set(GameplaySet::Spawning)
+                .run_if(in_state(crate::states::GameState::Playing)),
+        );
+    }
+}
+
+// Where a wave's zombies come from, picked per wave in the wave scripts
+#[derive(Debug, Clone, Default, Deserialize)]
+pub enum SpawnStrategy {
+    // Any spawn point in an unlocked section
+    #[default]
+    SpawnPoints,
+    // Only the listed spawn points (their section still has to be unlocked)
+    Named(Vec<String>),
+    // Just outside what the camera can see
+    OffscreenRing,
+    // Anywhere on the map
+    Anywhere,
+}
+
+// Ground marker shown before a zombie appears
+#[derive(Component)]
+pub struct SpawnTelegraph {
+    pub archetype: ZombieArchetype,
+    pub scaling: ZombieScaling,
+    pub timer: Timer,
+}
+
+const TELEGRAPH_SECONDS: f32 = 0.8;
+const BOSS_TELEGRAPH_SECONDS: f32 = 1.5;
+const SPAWN_ATTEMPTS: usize = 12;
+// Extra distance past the edge of the screen for OffscreenRing
+const OFFSCREEN_MARGIN: f32 = 60.0;
+
+#[derive(SystemParam)]
+pub struct SpawnContext<'w, 's> {
+    spawn_points: Query<'w, 's, (&'static Transform, &'static SpawnPoint)>,
+    walls: Query<'w, 's, (&'static Transform, &'static Collider), With<Wall>>,
+    players: Query<'w, 's, &'static Transform, With<Player>>,
+    unlocked: Res<'w, UnlockedSections>,
+    bounds: Res<'w, MapBounds>,
+    rng: ResMut<'w, GameRng>,
+}
+
+impl SpawnContext<'_, '_> {
+    // Picks a spot for a zombie of `size` that isn't in a wall or a locked
+    // section and is at least `min_player_distance` away from every player. If
+    // nothing qualifies, the furthest valid candidate wins so the wave never
+    // stalls, and failing that an opened spawn point.
+    pub fn pick_position(
+        &mut self,
+        strategy: &SpawnStrategy,
+        min_player_distance: f32,
+        size: f32,
+    ) -> Vec2 {
+        // Take the stream out so the helpers below can keep borrowing self
+        let stream = self.rng.stream(RngStream::Spawns);
+        let mut rng = std::mem::replace(stream, StdRng::seed_from_u64(0));
+        let position = self.search(&mut rng, strategy, min_player_distance, size);
+        *self.rng.stream(RngStream::Spawns) = rng;
+        position
+    }
+
+    fn search(
+        &self,
+        rng: &mut StdRng,
+        strategy: &SpawnStrategy,
+        min_player_distance: f32,
+        size: f32,
+    ) -> Vec2 {
+        let half_size = Vec2::splat(size / 2.0);
+        let players: Vec<Vec2> = self
+            .players
+            .iter()
+            .map(|transform| transform.translation.truncate())
+            .collect();
+
+        let mut fallback: Option<(Vec2, f32)> = None;
+        for _ in 0..SPAWN_ATTEMPTS {
+            let candidate = self.bounds.clamp(self.candidate(strategy, rng), half_size);
+            // Zombies can't path through closed doors, so one in a locked wing
+            // would never reach anyone and the wave could never be cleared
+            if !self.unlocked.is_unlocked(section_at(candidate))
+                || self.overlaps_wall(candidate, half_size)
+            {
+                continue;
+            }
+
+            let distance = players
+                .iter()
+                .map(|player| player.distance(candidate))
+                .fold(f32::MAX, f32::min);
+            if distance >= min_player_distance {
+                return candidate;
+            }
+            if fallback.is_none_or(|(_, best)| distance > best) {
+                fallback = Some((candidate, distance));
+            }
+        }
+
+        if let Some((position, _)) = fallback {
+            return position;
+        }
+        // Spawn points sit clear of the walls in opened sections
+        let point = self.random_point(rng, |_| true);
+        self.bounds.clamp(point, half_size)
+    }
+
+    fn candidate(&self, strategy: &SpawnStrategy, rng: &mut impl Rng) -> Vec2 {
+        match strategy {
+            SpawnStrategy::SpawnPoints => self.random_point(rng, |_| true),
+            SpawnStrategy::Named(names) => {
+                self.random_point(rng, |point| names.contains(&point.name))
+            }
+            SpawnStrategy::OffscreenRing => self.offscreen(rng),
+            SpawnStrategy::Anywhere => self.anywhere(rng),
+        }
+    }
+
+    fn random_point(&self, rng: &mut impl Rng, filter: impl Fn(&SpawnPoint) -> bool) -> Vec2 {
+        // Only spawn points in opened sections are active
+        let active: Vec<Vec2> = self
+            .spawn_points
+            .iter()
+            .filter(|(_, point)| self.unlocked.is_unlocked(point.section) && filter(point))
+            .map(|(transform, _)| transform.translation.truncate())
+            .collect();
+
+        if active.is_empty() {
+            return self.anywhere(rng);
+        }
+
+        // Spread zombies around the spawn point
+        let point = active[rng.random_range(0..active.len())];
+        point + Vec2::new(rng.random_range(-20.0..20.0), rng.random_range(-20.0..20.0))
+    }
+
+    // Around the view the camera settles on when centred on the players. The
+    // live camera also has shake, zoom and cursor look-ahead, none of which a
+    // replay can reproduce, so those are left out.
+    fn offscreen(&self, rng: &mut impl Rng) -> Vec2 {
+        let players: Vec<Vec2> = self
+            .players
+            .iter()
+            .map(|transform| transform.translation.truncate())
+            .collect();
+        if players.is_empty() {
+            return self.anywhere(rng);
+        }
+
+        let half_view = VIEW_SIZE / 2.0;
+        let group = players.iter().sum::<Vec2>() / players.len() as f32;
+        let center = self.bounds.clamp_view(group, half_view);
+        let radius = half_view.length() + OFFSCREEN_MARGIN;
+        let angle = rng.random_range(0.0..std::f32::consts::TAU);
+        center + Vec2::from_angle(angle) * radius
+    }
+
+    fn anywhere(&self, rng: &mut impl Rng) -> Vec2 {
+        Vec2::new(
+            rng.random_range(self.bounds.min.x..self.bounds.max.x),
+            rng.random_range(self.bounds.min.y..self.bounds.max.y),
+        )
+    }
+
+    fn overlaps_wall(&self, position: Vec2, half_size: Vec2) -> bool {
+        self.walls.iter().any(|(w_transform, w_collider)| {
+            let w_min = w_transform.translation.truncate() - w_collider.size / 2.0;
+            let w_max = w_transform.translation.truncate() + w_collider.size / 2.0;
+            let z_min = position - half_size;
+            let z_max = position + half_size;
+            z_min.x < w_max.x && z_max.x > w_min.x && z_min.y < w_max.y && z_max.y > w_min.y
+        })
+    }
+}
+
+// Puts down a marker; the zombie itself appears once the telegraph runs out
+pub fn queue_zombie(
+    commands: &mut Commands,
+    archetype: ZombieArchetype,
+    position: Vec2,
+    scaling: ZombieScaling,
+) {
+    let size = archetype.stats().size * 1.6;
+    let seconds = if archetype == ZombieArchetype::Boss {
+        BOSS_TELEGRAPH_SECONDS
+    } else {
+        TELEGRAPH_SECONDS
+    };
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgba(1.0, 0.0, 0.0, 0.3), // Faint Red Marker
+                custom_size: Some(Vec2::splat(size)),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(0.1)),
+            ..default()
+        },
+        SpawnTelegraph {
+            archetype,
+            scaling,
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        },
+    ));
+}
+
+fn hatch_telegraphs(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &Transform, &mut Sprite, &mut SpawnTelegraph)>,
+) {
+    for (entity, transform, mut sprite, mut telegraph) in &mut query {
+        telegraph.timer.tick(time.delta());
+
+        // Blink faster as the zombie is about to appear
+        let t = telegraph.timer.fraction();
+        let blink = (t * t * 40.0).sin() * 0.5 + 0.5;
+        sprite.color.set_alpha(0.15 + 0.35 * blink);
+
+        if telegraph.timer.finished() {
+            commands.entity(entity).despawn();
+            spawn_zombie(
+                &mut commands,
+                telegraph.archetype,
+                transform.translation.truncate(),
+                telegraph.scaling,
+            );
+        }
+    }
+}
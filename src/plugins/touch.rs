@@ -0,0 +1,410 @@
+use crate::plugins::controls::{Action, STICK_DEADZONE, VirtualInput, read_player_input};
+use crate::states::{GameState, WavePhase};
+use bevy::input::InputSystem;
+use bevy::input::mouse::MouseMotion;
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+pub struct TouchControlsPlugin;
+
+impl Plugin for TouchControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchControls>()
+            .init_resource::<TouchTracking>()
+            .add_systems(Startup, setup_touch_overlay)
+            .add_systems(
+                PreUpdate,
+                // Before the input is read, so a tap counts on the same frame
+                (detect_touch_controls, read_touch_controls)
+                    .chain()
+                    .after(InputSystem)
+                    .after(UiSystem::Focus)
+                    .before(read_player_input),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_touch_overlay,
+                    update_stick_visuals,
+                    touch_friendly_buttons,
+                ),
+            );
+    }
+}
+
+// How far a stick can be dragged from where the thumb went down
+const STICK_RADIUS: f32 = 60.0;
+const KNOB_RADIUS: f32 = 25.0;
+// Space between the resting sticks and the edge of the screen
+const STICK_MARGIN: f32 = 40.0;
+// Smallest comfortable tap target
+const TOUCH_TARGET: f32 = 48.0;
+
+// On-screen buttons, top to bottom along the right edge
+const TOUCH_BUTTONS: [(Action, &str); 8] = [
+    (Action::Pause, "Pause"),
+    (Action::Back, "Menu"),
+    (Action::Confirm, "Continue"),
+    (Action::Reload, "Reload"),
+    (Action::BuyUpgrade, "Fire Rate"),
+    (Action::BuyMedkit, "Medkit"),
+    (Action::NextWeapon, "Swap"),
+    (Action::Interact, "Door"),
+];
+
+// Switched on by the first touch and off again by the keyboard or mouse
+#[derive(Resource, Default)]
+pub struct TouchControls {
+    pub active: bool,
+}
+
+// A thumb on one of the sticks, in window pixels
+#[derive(Clone, Copy)]
+struct StickTouch {
+    id: u64,
+    origin: Vec2,
+    position: Vec2,
+}
+
+impl StickTouch {
+    // Up to unit length, y up like a pad stick
+    fn value(&self) -> Vec2 {
+        let offset = (self.position - self.origin) / STICK_RADIUS;
+        Vec2::new(offset.x, -offset.y).clamp_length_max(1.0)
+    }
+}
+
+// Which finger is doing what. The left half of the screen moves, the right
+// half aims and fires, and buttons are held by whichever finger went down on them.
+#[derive(Resource, Default)]
+struct TouchTracking {
+    movement: Option<StickTouch>,
+    aim: Option<StickTouch>,
+    buttons: Vec<(u64, Action)>,
+}
+
+#[derive(Component)]
+struct TouchOverlay;
+
+#[derive(Component)]
+struct TouchButton(Action);
+
+#[derive(Component, Clone, Copy, PartialEq)]
+enum StickSide {
+    Movement,
+    Aim,
+}
+
+#[derive(Component)]
+struct StickKnob;
+
+fn detect_touch_controls(
+    touches: Res<Touches>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut controls: ResMut<TouchControls>,
+) {
+    let desktop_used = mouse_motion.read().count() > 0
+        || keyboard.get_just_pressed().next().is_some()
+        || mouse.get_just_pressed().next().is_some();
+
+    if !controls.active && touches.any_just_pressed() {
+        controls.active = true;
+        info!("Touch controls on");
+    } else if controls.active && desktop_used {
+        controls.active = false;
+        info!("Touch controls off");
+    }
+}
+
+fn read_touch_controls(
+    touches: Res<Touches>,
+    controls: Res<TouchControls>,
+    state: Res<State<GameState>>,
+    windows: Query<&Window>,
+    buttons: Query<(&TouchButton, &Node, &GlobalTransform)>,
+    mut tracking: ResMut<TouchTracking>,
+    mut virtual_input: ResMut<VirtualInput>,
+) {
+    if !controls.active {
+        // Let go of everything when the keyboard or mouse takes over
+        if controls.is_changed() {
+            *tracking = TouchTracking::default();
+            *virtual_input = VirtualInput::default();
+        }
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let playing = *state.get() == GameState::Playing;
+
+    for touch in touches.iter_just_pressed() {
+        let position = touch.position();
+        // Hidden buttons have no size, so can't be hit
+        let on_button = buttons
+            .iter()
+            .find(|(_, node, transform)| {
+                node.size() != Vec2::ZERO && node.logical_rect(transform).contains(position)
+            })
+            .map(|(TouchButton(action), ..)| *action);
+
+        let stick = StickTouch {
+            id: touch.id(),
+            origin: position,
+            position,
+        };
+        if let Some(action) = on_button {
+            tracking.buttons.push((touch.id(), action));
+        } else if !playing {
+            continue;
+        } else if position.x < window.width() / 2.0 {
+            tracking.movement.get_or_insert(stick);
+        } else {
+            tracking.aim.get_or_insert(stick);
+        }
+    }
+
+    // Follow the fingers still down, let go of the rest
+    let follow = |stick: Option<StickTouch>| {
+        stick.and_then(|stick| {
+            touches.get_pressed(stick.id).map(|touch| StickTouch {
+                position: touch.position(),
+                ..stick
+            })
+        })
+    };
+    tracking.movement = follow(tracking.movement).filter(|_| playing);
+    tracking.aim = follow(tracking.aim).filter(|_| playing);
+    tracking
+        .buttons
+        .retain(|(id, _)| touches.get_pressed(*id).is_some());
+
+    virtual_input.left_stick = tracking.movement.map(|stick| stick.value());
+    virtual_input.right_stick = tracking.aim.map(|stick| stick.value());
+
+    // Aiming fires, like holding the trigger with the stick pushed
+    let firing = virtual_input
+        .right_stick
+        .is_some_and(|stick| stick.length() > STICK_DEADZONE);
+    virtual_input.buttons.clear();
+    for action in Action::ALL {
+        let held = tracking.buttons.iter().any(|(_, held)| *held == action)
+            || (action == Action::Fire && firing);
+        if held {
+            virtual_input.buttons.press(action);
+        } else {
+            virtual_input.buttons.release(action);
+        }
+    }
+}
+
+fn setup_touch_overlay(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    display: Display::None,
+                    ..default()
+                },
+                // Above the HUD and the pause screen
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            TouchOverlay,
+        ))
+        .with_children(|parent| {
+            for side in [StickSide::Movement, StickSide::Aim] {
+                parent
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                width: Val::Px(STICK_RADIUS * 2.0),
+                                height: Val::Px(STICK_RADIUS * 2.0),
+                                ..default()
+                            },
+                            background_color: Color::srgba(1.0, 1.0, 1.0, 0.15).into(),
+                            border_radius: BorderRadius::MAX,
+                            ..default()
+                        },
+                        side,
+                    ))
+                    .with_children(|base| {
+                        base.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    position_type: PositionType::Absolute,
+                                    width: Val::Px(KNOB_RADIUS * 2.0),
+                                    height: Val::Px(KNOB_RADIUS * 2.0),
+                                    ..default()
+                                },
+                                background_color: Color::srgba(1.0, 1.0, 1.0, 0.4).into(),
+                                border_radius: BorderRadius::MAX,
+                                ..default()
+                            },
+                            StickKnob,
+                        ));
+                    });
+            }
+
+            // Action buttons down the right edge, above the aim stick
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        right: Val::Px(20.0),
+                        top: Val::Px(80.0),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|column| {
+                    for (action, label) in TOUCH_BUTTONS {
+                        column
+                            .spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        width: Val::Px(110.0),
+                                        height: Val::Px(TOUCH_TARGET + 8.0),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    background_color: Color::srgba(0.2, 0.2, 0.2, 0.6).into(),
+                                    border_radius: BorderRadius::all(Val::Px(8.0)),
+                                    ..default()
+                                },
+                                TouchButton(action),
+                            ))
+                            .with_children(|button| {
+                                button.spawn(TextBundle::from_section(
+                                    label,
+                                    TextStyle {
+                                        font_size: 20.0,
+                                        color: Color::WHITE,
+                                        ..default()
+                                    },
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+// Which buttons make sense right now
+fn button_shown(action: Action, state: GameState, phase: Option<WavePhase>) -> bool {
+    let intermission = phase == Some(WavePhase::Intermission);
+    match (action, state) {
+        (Action::Pause, GameState::Playing | GameState::Paused) => true,
+        (Action::Back, GameState::Paused) => true,
+        (Action::Confirm, GameState::GameOver | GameState::Win) => true,
+        (Action::Confirm | Action::Reload | Action::BuyUpgrade | Action::BuyMedkit, _) => {
+            intermission
+        }
+        (Action::NextWeapon | Action::Interact, GameState::Playing) => true,
+        _ => false,
+    }
+}
+
+fn update_touch_overlay(
+    controls: Res<TouchControls>,
+    state: Res<State<GameState>>,
+    phase: Option<Res<State<WavePhase>>>,
+    tracking: Res<TouchTracking>,
+    mut overlay: Query<&mut Style, (With<TouchOverlay>, Without<TouchButton>)>,
+    mut buttons: Query<(&TouchButton, &mut Style, &mut BackgroundColor)>,
+) {
+    let state = *state.get();
+    let phase = phase.map(|phase| *phase.get());
+    let shown = controls.active && !matches!(state, GameState::Dashboard | GameState::Lobby);
+    for mut style in &mut overlay {
+        style.display = if shown { Display::Flex } else { Display::None };
+    }
+
+    for (TouchButton(action), mut style, mut color) in &mut buttons {
+        style.display = if button_shown(*action, state, phase) {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        let held = tracking.buttons.iter().any(|(_, held)| held == action);
+        *color = if held {
+            Color::srgba(0.5, 0.5, 0.5, 0.8).into()
+        } else {
+            Color::srgba(0.2, 0.2, 0.2, 0.6).into()
+        };
+    }
+}
+
+// Sticks rest in the bottom corners and jump to wherever a thumb goes down
+fn update_stick_visuals(
+    state: Res<State<GameState>>,
+    tracking: Res<TouchTracking>,
+    windows: Query<&Window>,
+    mut bases: Query<(&StickSide, &mut Style, &Children), Without<StickKnob>>,
+    mut knobs: Query<&mut Style, With<StickKnob>>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let resting_y = window.height() - STICK_MARGIN - STICK_RADIUS;
+    let playing = *state.get() == GameState::Playing;
+
+    for (side, mut style, children) in &mut bases {
+        let (stick, resting) = match side {
+            StickSide::Movement => (
+                tracking.movement,
+                Vec2::new(STICK_MARGIN + STICK_RADIUS, resting_y),
+            ),
+            StickSide::Aim => (
+                tracking.aim,
+                Vec2::new(window.width() - STICK_MARGIN - STICK_RADIUS, resting_y),
+            ),
+        };
+        let centre = stick.map_or(resting, |stick| stick.origin);
+        let offset = stick.map_or(Vec2::ZERO, |stick| {
+            (stick.position - stick.origin).clamp_length_max(STICK_RADIUS)
+        });
+
+        style.display = if playing {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        style.left = Val::Px(centre.x - STICK_RADIUS);
+        style.top = Val::Px(centre.y - STICK_RADIUS);
+        for &child in children {
+            if let Ok(mut knob) = knobs.get_mut(child) {
+                knob.left = Val::Px(STICK_RADIUS - KNOB_RADIUS + offset.x);
+                knob.top = Val::Px(STICK_RADIUS - KNOB_RADIUS + offset.y);
+            }
+        }
+    }
+}
+
+// Menu buttons grow to a comfortable size for fingers while touch is on
+fn touch_friendly_buttons(
+    controls: Res<TouchControls>,
+    added: Query<(), Added<Button>>,
+    mut buttons: Query<&mut Style, With<Button>>,
+) {
+    if !controls.is_changed() && added.is_empty() {
+        return;
+    }
+    let min_size = if controls.active {
+        Val::Px(TOUCH_TARGET)
+    } else {
+        Val::Auto
+    };
+    for mut style in &mut buttons {
+        style.min_width = min_size;
+        style.min_height = min_size;
+    }
+}
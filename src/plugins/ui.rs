@@ -1,24 +1,32 @@
 use bevy::prelude::*;
+use crate::plugins::player::{Downed, MAX_PLAYERS, Player, PlayerId, WeaponStats};
 use crate::plugins::wave::WaveState;
 
 #[derive(Component)]
 struct WaveText;
 
+// One health and one ammo line per player slot, hidden when nobody is in it
 #[derive(Component)]
-struct HealthText;
+struct HealthText(usize);
 
 #[derive(Component)]
 struct MoneyText;
 
 #[derive(Component)]
-struct AmmoText;
+struct AmmoText(usize);
+
+// Line height for the stacked per-player lines
+const PLAYER_LINE: f32 = 24.0;
+
+#[derive(Component)]
+struct ObjectiveText;
 
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, setup_ui)
-           .add_systems(Update, (update_wave_ui, update_health_ui, update_money_ui, update_ammo_ui));
+           .add_systems(Update, (update_wave_ui, update_health_ui, update_money_ui, update_ammo_ui, update_objective_ui));
     }
 }
 
@@ -31,14 +39,41 @@ fn update_money_ui(
     }
 }
 
+fn update_objective_ui(
+    objective: Res<crate::plugins::game_mode::Objective>,
+    mut query: Query<&mut Text, With<ObjectiveText>>,
+) {
+    for mut text in &mut query {
+        text.sections[0].value = objective.text.clone();
+    }
+}
+
 fn update_health_ui(
-    player_query: Query<&crate::components::Health, With<crate::plugins::player::Player>>,
-    mut text_query: Query<&mut Text, With<HealthText>>,
+    player_query: Query<(&PlayerId, &crate::components::Health, Option<&Downed>), With<Player>>,
+    mut text_query: Query<(&mut Text, &mut Visibility, &HealthText)>,
 ) {
-    if let Ok(health) = player_query.get_single() {
-        for mut text in &mut text_query {
-            text.sections[0].value = format!("Health: {:.0}/{:.0}", health.current, health.max);
-        }
+    let solo = player_query.iter().count() <= 1;
+    for (mut text, mut visibility, slot) in &mut text_query {
+        let Some((id, health, downed)) = player_query.iter().find(|(id, ..)| id.0 == slot.0) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Inherited;
+
+        let label = if solo {
+            String::new()
+        } else {
+            format!("{} ", id.label())
+        };
+        text.sections[0].value = match downed {
+            Some(downed) => format!(
+                "{}DOWN - Revive {:.0}%",
+                label,
+                downed.revive_progress * 100.0
+            ),
+            None => format!("{}Health: {:.0}/{:.0}", label, health.current, health.max),
+        };
+        text.sections[0].style.color = if solo { Color::WHITE } else { id.color() };
     }
 }
 
@@ -55,29 +90,51 @@ fn setup_ui(mut commands: Commands) {
             ..default()
         })
         .with_children(|parent| {
-            // Left side
-            parent.spawn((
-                TextBundle::from_section(
-                    "Health: 100",
-                    TextStyle {
-                        font_size: 20.0,
-                        color: Color::WHITE,
+            // Left side, health stacked down from the top and ammo up from the bottom
+            for slot in 0..MAX_PLAYERS {
+                let offset = 10.0 + slot as f32 * PLAYER_LINE;
+                parent.spawn((
+                    TextBundle::from_section(
+                        "Health: 100",
+                        TextStyle {
+                            font_size: 20.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    )
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(offset),
+                        left: Val::Px(10.0),
                         ..default()
-                    },
-                )
-                .with_style(Style {
-                    position_type: PositionType::Absolute,
-                    top: Val::Px(10.0),
-                    left: Val::Px(10.0),
-                    ..default()
-                }),
-                HealthText,
-            ));
+                    }),
+                    HealthText(slot),
+                ));
 
-            // Ammo
-            parent.spawn((
+                // Ammo
+                parent.spawn((
+                    TextBundle::from_section(
+                        "Ammo: 30/30",
+                        TextStyle {
+                            font_size: 20.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    )
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(offset),
+                        left: Val::Px(10.0),
+                        ..default()
+                    }),
+                    AmmoText(slot),
+                ));
+            }
+
+            // Right side: Wave Info & Money
+             parent.spawn((
                 TextBundle::from_section(
-                    "Ammo: 30/30",
+                    "Money: $0",
                     TextStyle {
                         font_size: 20.0,
                         color: Color::WHITE,
@@ -86,17 +143,15 @@ fn setup_ui(mut commands: Commands) {
                 )
                 .with_style(Style {
                     position_type: PositionType::Absolute,
-                    bottom: Val::Px(10.0),
-                    left: Val::Px(10.0),
+                    top: Val::Px(40.0), // Below Wave
+                    right: Val::Px(10.0),
                     ..default()
                 }),
-                AmmoText,
+                MoneyText,
             ));
-
-            // Right side: Wave Info & Money
-             parent.spawn((
+            parent.spawn((
                 TextBundle::from_section(
-                    "Money: $0",
+                    "Wave: 1",
                     TextStyle {
                         font_size: 20.0,
                         color: Color::WHITE,
@@ -105,28 +160,31 @@ fn setup_ui(mut commands: Commands) {
                 )
                 .with_style(Style {
                     position_type: PositionType::Absolute,
-                    top: Val::Px(40.0), // Below Wave
+                    top: Val::Px(10.0),
                     right: Val::Px(10.0),
                     ..default()
                 }),
-                MoneyText,
+                WaveText,
             ));
+
+            // Top center: what the current game mode wants from the player
             parent.spawn((
                 TextBundle::from_section(
-                    "Wave: 1",
+                    "",
                     TextStyle {
                         font_size: 20.0,
-                        color: Color::WHITE,
+                        color: Color::srgb(1.0, 0.9, 0.3),
                         ..default()
                     },
                 )
                 .with_style(Style {
                     position_type: PositionType::Absolute,
                     top: Val::Px(10.0),
-                    right: Val::Px(10.0),
+                    left: Val::Percent(50.0),
+                    margin: UiRect::left(Val::Px(-160.0)),
                     ..default()
                 }),
-                WaveText,
+                ObjectiveText,
             ));
         });
 }
@@ -136,17 +194,74 @@ fn update_wave_ui(
     mut query: Query<&mut Text, With<WaveText>>,
 ) {
     for mut text in &mut query {
-        text.sections[0].value = format!("Wave: {}", wave_state.current_wave);
+        text.sections[0].value = match wave_state.total_waves {
+            Some(total) => format!("Wave: {}/{}", wave_state.current_wave, total),
+            None => format!("Wave: {}", wave_state.current_wave),
+        };
     }
 }
 
 fn update_ammo_ui(
-    player_query: Query<&crate::plugins::player::WeaponStats, With<crate::plugins::player::Player>>,
-    mut text_query: Query<&mut Text, With<AmmoText>>,
+    player_query: Query<(&PlayerId, &WeaponStats), With<Player>>,
+    mut text_query: Query<(&mut Text, &mut Visibility, &AmmoText)>,
 ) {
-    if let Ok(stats) = player_query.get_single() {
-        for mut text in &mut text_query {
-            text.sections[0].value = format!("Ammo: {}/{}", stats.current_ammo, stats.max_ammo);
-        }
+    let solo = player_query.iter().count() <= 1;
+    for (mut text, mut visibility, slot) in &mut text_query {
+        let Some((id, stats)) = player_query.iter().find(|(id, _)| id.0 == slot.0) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Inherited;
+
+        let label = if solo {
+            String::new()
+        } else {
+            format!("{} ", id.label())
+        };
+        text.sections[0].value =
+            format!("{}Ammo: {}/{}", label, stats.current_ammo, stats.max_ammo);
+        text.sections[0].style.color = if solo { Color::WHITE } else { id.color() };
     }
 }
+
+// Grey menu button with a centred label, shared by the menu screens
+pub fn spawn_button<M: Component>(parent: &mut ChildBuilder, label: impl Into<String>, width: f32, marker: M) {
+    spawn_tagged_button(parent, label, width, marker, ());
+}
+
+// Same as spawn_button, with `tag` on the label so a screen can rewrite it later
+pub fn spawn_tagged_button<M: Component>(
+    parent: &mut ChildBuilder,
+    label: impl Into<String>,
+    width: f32,
+    marker: M,
+    tag: impl Bundle,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(width),
+                    height: Val::Px(40.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|p| {
+            p.spawn((
+                TextBundle::from_section(
+                    label,
+                    TextStyle {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                ),
+                tag,
+            ));
+        });
+}
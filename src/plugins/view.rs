@@ -0,0 +1,20 @@
+use crate::states::RunTeardown;
+use crate::systems::camera::{CameraFollow, camera_follow, reset_camera, setup_camera};
+use crate::systems::rendering::y_sort;
+use bevy::prelude::*;
+
+// The camera that follows the player, and sprite depth sorting
+pub struct ViewPlugin;
+
+impl Plugin for ViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraFollow>()
+            .add_systems(Startup, setup_camera)
+            .add_systems(RunTeardown, reset_camera)
+            .add_systems(
+                PostUpdate,
+                camera_follow.before(bevy::transform::TransformSystem::TransformPropagate),
+            )
+            .add_systems(Update, y_sort);
+    }
+}
@@ -1,27 +1,106 @@
 use crate::components::Zombie;
+use crate::plugins::enemy::{ZombieArchetype, ZombieScaling};
+use crate::plugins::game_mode::AllWavesCleared;
+use crate::plugins::net_client::NetClient;
+use crate::plugins::run_modifiers::RunModifiers;
+use crate::plugins::simulation::GameplaySet;
+use crate::plugins::spawning::{SpawnContext, SpawnTelegraph, queue_zombie};
+use crate::plugins::wave_script::{WaveDefinition, WaveScripts};
+use crate::states::WavePhase;
 use bevy::prelude::*;
 
 pub struct WavePlugin;
 
 impl Plugin for WavePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(WaveState {
-            current_wave: 1,
-            zombies_remaining: 5,
-            spawn_timer: Timer::from_seconds(2.0, TimerMode::Repeating),
-        })
-        .add_systems(
-            Update,
-            wave_manager.run_if(in_state(crate::states::GameState::Playing)),
-        );
+        app.insert_resource(WaveScripts::load())
+            .init_resource::<WaveState>()
+            // Every run starts from a clean wave state, however the last one ended
+            .add_systems(crate::states::RunTeardown, reset_waves)
+            .add_systems(
+                OnEnter(crate::states::GameState::GameOver),
+                record_endless_best.run_if(not(crate::plugins::replay::watching_replay)),
+            )
+            // Quitting with ESC never reaches GameOver, so check again on the way
+            // out, before the wave count and the replay are cleared. A joined
+            // client only mirrors the host's wave.
+            .add_systems(
+                crate::states::RunTeardown,
+                record_endless_best
+                    .before(reset_waves)
+                    .before(crate::plugins::replay::stop_replay)
+                    .run_if(not(crate::plugins::replay::watching_replay))
+                    .run_if(not(resource_exists::<NetClient>)),
+            )
+            .add_systems(
+                FixedUpdate,
+                wave_manager
+                    .in_set(GameplaySet::Waves)
+                    .run_if(in_state(WavePhase::Combat)),
+            );
     }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct WaveState {
+    // 1-based, 0 means the run hasn't started its first wave yet
     pub current_wave: u32,
+    // None when the waves never run out (Endless, or a mode that outlasts the script)
+    pub total_waves: Option<u32>,
+    // Zombies (including the boss) still to be spawned this wave
     pub zombies_remaining: u32,
+    pub elapsed: f32,
     pub spawn_timer: Timer,
+    pub groups: Vec<GroupProgress>,
+    pub boss_pending: bool,
+    // Countdown to the next wave, Some while between waves
+    pub intermission: Option<Timer>,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct GroupProgress {
+    pub spawned: u32,
+}
+
+impl WaveState {
+    pub fn start_wave(&mut self, wave: u32, total_waves: Option<u32>, definition: &WaveDefinition) {
+        self.current_wave = wave;
+        self.total_waves = total_waves;
+        self.zombies_remaining = definition.total_zombies();
+        self.elapsed = 0.0;
+        self.spawn_timer =
+            Timer::from_seconds(definition.spawn_interval.at(0.0), TimerMode::Repeating);
+        self.groups = vec![GroupProgress::default(); definition.groups.len()];
+        self.boss_pending = definition.boss.is_some();
+    }
+
+    // 0..1 based on how many of this wave's zombies have been spawned
+    fn progress(&self, definition: &WaveDefinition) -> f32 {
+        let total = definition.total_zombies();
+        if total == 0 {
+            return 1.0;
+        }
+        (total - self.zombies_remaining) as f32 / total as f32
+    }
+}
+
+pub fn reset_waves(mut wave_state: ResMut<WaveState>) {
+    *wave_state = WaveState::default();
+}
+
+fn record_endless_best(
+    wave_state: Res<WaveState>,
+    settings: Res<crate::plugins::settings::GameSettings>,
+    mut global_stats: ResMut<crate::plugins::progression::GlobalPlayerStats>,
+) {
+    if settings.difficulty != Some(crate::plugins::settings::Difficulty::Endless) {
+        return;
+    }
+
+    if wave_state.current_wave > global_stats.best_endless_wave {
+        global_stats.best_endless_wave = wave_state.current_wave;
+        info!("New Endless Record: Wave {}", wave_state.current_wave);
+    }
 }
 
 fn wave_manager(
@@ -29,80 +108,120 @@ fn wave_manager(
     time: Res<Time>,
     mut wave_state: ResMut<WaveState>,
     zombies: Query<&Zombie>,
-    mut next_state: ResMut<NextState<crate::states::GameState>>,
+    mut cleared_events: EventWriter<AllWavesCleared>,
+    mut next_phase: ResMut<NextState<WavePhase>>,
     settings: Res<crate::plugins::settings::GameSettings>,
+    scripts: Res<WaveScripts>,
+    telegraphs: Query<&SpawnTelegraph>,
+    mut spawn_context: SpawnContext,
+    run_modifiers: Res<RunModifiers>,
+    balance: Res<crate::plugins::settings::Balance>,
 ) {
     // Difficulty is guaranteed to be set if we are in Playing state
     let difficulty = settings.difficulty.expect("Difficulty not set!");
+    let total_waves = scripts.total_waves(&settings);
+
+    if wave_state.current_wave == 0 {
+        let first = scripts
+            .wave(&settings, 1)
+            .expect("Wave script has no waves!");
+        wave_state.start_wave(1, total_waves, &first);
+        info!("Starting Wave 1");
+    }
+
+    // Pausing drops the Intermission sub-state, so go back to it if we were counting down
+    if wave_state.intermission.is_some() {
+        next_phase.set(WavePhase::Intermission);
+        return;
+    }
 
-    // Check if wave is cleared
-    if wave_state.zombies_remaining == 0 && zombies.iter().count() == 0 {
-        // Check Win Condition
-        if wave_state.current_wave >= difficulty.max_waves() {
-            info!("YOU WIN!");
-            next_state.set(crate::states::GameState::Win);
+    // Telegraphed zombies haven't appeared yet but still count as alive
+    if wave_state.zombies_remaining == 0 && zombies.is_empty() && telegraphs.is_empty() {
+        // Out of waves, the game mode decides what that means
+        if total_waves.is_some_and(|total| wave_state.current_wave >= total) {
+            cleared_events.send(AllWavesCleared);
             return;
         }
 
-        // Start next wave
-        wave_state.current_wave += 1;
-        wave_state.zombies_remaining = 5 + wave_state.current_wave * 2;
-        info!("Starting Wave {}", wave_state.current_wave);
+        // Breather before the next wave
+        wave_state.intermission = Some(Timer::from_seconds(
+            scripts.intermission(difficulty),
+            TimerMode::Once,
+        ));
+        next_phase.set(WavePhase::Intermission);
+        info!("Wave {} cleared!", wave_state.current_wave);
+        return;
+    }
+
+    let Some(definition) = scripts.wave(&settings, wave_state.current_wave) else {
+        return;
+    };
+    let definition = definition.as_ref();
+    wave_state.elapsed += time.delta_seconds();
+    let mut scaling = run_modifiers.zombie_scaling(definition.scaling());
+    scaling.health *= balance.zombie_health(wave_state.current_wave);
+
+    // Boss arrives on its own schedule, independent of the spawn ticks
+    if wave_state.boss_pending
+        && let Some(boss) = &definition.boss
+        && wave_state.elapsed >= boss.delay
+    {
+        spawn_from_script(
+            &mut commands,
+            boss.archetype,
+            definition,
+            scaling,
+            &mut spawn_context,
+        );
+        wave_state.boss_pending = false;
+        wave_state.zombies_remaining -= 1;
+        info!("A {:?} has appeared!", boss.archetype);
     }
 
     // Spawn zombies over time
     wave_state.spawn_timer.tick(time.delta());
-    if wave_state.spawn_timer.just_finished() && wave_state.zombies_remaining > 0 {
-        let spawn_count = match difficulty {
-            crate::plugins::settings::Difficulty::Easy => 1,
-            crate::plugins::settings::Difficulty::Medium => 2,
-            crate::plugins::settings::Difficulty::Hard => 3,
-        };
-
-        for _ in 0..spawn_count {
-            if wave_state.zombies_remaining > 0 {
-                spawn_random_zombie(&mut commands, wave_state.current_wave);
+    if wave_state.spawn_timer.just_finished() {
+        let elapsed = wave_state.elapsed;
+        for (index, group) in definition.groups.iter().enumerate() {
+            if elapsed < group.delay {
+                continue;
+            }
+
+            let left_in_group = group.count - wave_state.groups[index].spawned;
+            for _ in 0..group.batch.min(left_in_group) {
+                spawn_from_script(
+                    &mut commands,
+                    group.archetype,
+                    definition,
+                    scaling,
+                    &mut spawn_context,
+                );
+                wave_state.groups[index].spawned += 1;
                 wave_state.zombies_remaining -= 1;
             }
         }
+
+        // Follow the spawn rate curve
+        let interval = definition
+            .spawn_interval
+            .at(wave_state.progress(definition));
+        wave_state
+            .spawn_timer
+            .set_duration(std::time::Duration::from_secs_f32(interval));
     }
 }
 
-fn spawn_random_zombie(commands: &mut Commands, wave: u32) {
-    let mut rng = rand::rng();
-    use rand::Rng;
-
-    let x = rng.random_range(-400.0..400.0);
-    let y = rng.random_range(-300.0..300.0);
-
-    // Zombie Size based on Level (Wave)
-    // Level 1 = 10x10, Level 2 = 20x20, Level 3 = 30x30
-    let (size_val, hp, reward) = if wave == 1 {
-        (10.0, 10.0, 10)
-    } else if wave == 2 {
-        (20.0, 20.0, 20)
-    } else {
-        (30.0, 40.0, 30) // Level 3 and above
-    };
-
-    let size = Vec2::new(size_val, size_val);
-
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::srgb(1.0, 0.0, 0.0), // Red Zombie
-                custom_size: Some(size),
-                ..default()
-            },
-            transform: Transform::from_xyz(x, y, 0.5),
-            ..default()
-        },
-        Zombie {
-            money_reward: reward,
-        },
-        crate::components::Health {
-            current: hp,
-            max: hp,
-        },
-    ));
+fn spawn_from_script(
+    commands: &mut Commands,
+    archetype: ZombieArchetype,
+    definition: &WaveDefinition,
+    scaling: ZombieScaling,
+    spawn_context: &mut SpawnContext,
+) {
+    let position = spawn_context.pick_position(
+        &definition.spawn,
+        definition.min_player_distance,
+        archetype.stats().size,
+    );
+    queue_zombie(commands, archetype, position, scaling);
 }
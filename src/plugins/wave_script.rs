@@ -0,0 +1,258 @@
+use crate::plugins::enemy::{ZombieArchetype, ZombieScaling};
+use crate::plugins::settings::{CustomDifficulty, Difficulty, GameSettings};
+use crate::plugins::spawning::SpawnStrategy;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::borrow::Cow;
+
+// Wave definitions live in assets/waves/*.ron. They are embedded at compile time
+// so the wasm build doesn't need an asset server round trip before the first wave.
+const EASY_SCRIPT: &str = include_str!("../../assets/waves/easy.ron");
+const MEDIUM_SCRIPT: &str = include_str!("../../assets/waves/medium.ron");
+const HARD_SCRIPT: &str = include_str!("../../assets/waves/hard.ron");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WaveScript {
+    pub waves: Vec<WaveDefinition>,
+    // Seconds of downtime between waves
+    #[serde(default = "default_intermission")]
+    pub intermission: f32,
+}
+
+fn default_intermission() -> f32 {
+    10.0
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WaveDefinition {
+    pub spawn_interval: SpawnCurve,
+    pub groups: Vec<SpawnGroup>,
+    #[serde(default)]
+    pub boss: Option<BossSpawn>,
+    #[serde(default)]
+    pub spawn: SpawnStrategy,
+    // Zombies never appear closer than this to the player
+    #[serde(default = "default_min_player_distance")]
+    pub min_player_distance: f32,
+    #[serde(default = "default_multiplier")]
+    pub health_multiplier: f32,
+    #[serde(default = "default_multiplier")]
+    pub speed_multiplier: f32,
+    #[serde(default = "default_multiplier")]
+    pub damage_multiplier: f32,
+    #[serde(default = "default_multiplier")]
+    pub reward_multiplier: f32,
+}
+
+fn default_min_player_distance() -> f32 {
+    250.0
+}
+
+fn default_multiplier() -> f32 {
+    1.0
+}
+
+// Seconds between spawn ticks, eased from `start` to `end` as the wave progresses
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct SpawnCurve {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl SpawnCurve {
+    pub fn at(&self, progress: f32) -> f32 {
+        let t = progress.clamp(0.0, 1.0);
+        (self.start + (self.end - self.start) * t).max(0.05)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpawnGroup {
+    pub archetype: ZombieArchetype,
+    pub count: u32,
+    // Zombies spawned per tick
+    #[serde(default = "default_batch")]
+    pub batch: u32,
+    // Seconds after the wave starts before this group begins spawning
+    #[serde(default)]
+    pub delay: f32,
+}
+
+fn default_batch() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BossSpawn {
+    pub archetype: ZombieArchetype,
+    #[serde(default)]
+    pub delay: f32,
+}
+
+impl WaveDefinition {
+    pub fn total_zombies(&self) -> u32 {
+        self.groups.iter().map(|group| group.count).sum::<u32>() + self.boss.iter().count() as u32
+    }
+
+    pub fn scaling(&self) -> ZombieScaling {
+        ZombieScaling {
+            health: self.health_multiplier,
+            speed: self.speed_multiplier,
+            damage: self.damage_multiplier,
+            reward: self.reward_multiplier,
+        }
+    }
+
+    // Layers the custom difficulty knobs on top of the wave's own tuning
+    fn with_modifiers(mut self, modifiers: &CustomDifficulty) -> Self {
+        let modifiers = modifiers.clamped();
+        self.spawn_interval.start /= modifiers.spawn_rate;
+        self.spawn_interval.end /= modifiers.spawn_rate;
+        self.health_multiplier *= modifiers.zombie_health;
+        self.speed_multiplier *= modifiers.zombie_speed;
+        self.damage_multiplier *= modifiers.zombie_damage;
+        self.reward_multiplier *= modifiers.reward;
+        self
+    }
+}
+
+#[derive(Resource)]
+pub struct WaveScripts {
+    pub easy: WaveScript,
+    pub medium: WaveScript,
+    pub hard: WaveScript,
+}
+
+impl WaveScripts {
+    pub fn load() -> Self {
+        Self {
+            easy: parse_script("easy", EASY_SCRIPT),
+            medium: parse_script("medium", MEDIUM_SCRIPT),
+            hard: parse_script("hard", HARD_SCRIPT),
+        }
+    }
+
+    // None for Endless and Custom, which have no script
+    pub fn get(&self, difficulty: Difficulty) -> Option<&WaveScript> {
+        match difficulty {
+            Difficulty::Easy => Some(&self.easy),
+            Difficulty::Medium => Some(&self.medium),
+            Difficulty::Hard => Some(&self.hard),
+            Difficulty::Endless | Difficulty::Custom => None,
+        }
+    }
+
+    // 1-based. None once the run is out of waves in a mode that ends there;
+    // other modes carry on with generated waves past the script
+    pub fn wave(&self, settings: &GameSettings, wave: u32) -> Option<Cow<'_, WaveDefinition>> {
+        let difficulty = settings.difficulty?;
+        if self.total_waves(settings).is_some_and(|total| wave > total) {
+            return None;
+        }
+
+        Some(match self.get(difficulty) {
+            Some(script) => match script.waves.get(wave as usize - 1) {
+                Some(definition) => Cow::Borrowed(definition),
+                None => Cow::Owned(endless_wave(wave)),
+            },
+            None if difficulty == Difficulty::Custom => {
+                Cow::Owned(endless_wave(wave).with_modifiers(&settings.custom))
+            }
+            None => Cow::Owned(endless_wave(wave)),
+        })
+    }
+
+    // None means the waves never run out
+    pub fn total_waves(&self, settings: &GameSettings) -> Option<u32> {
+        if !settings.game_mode.ends_after_last_wave() {
+            return None;
+        }
+        match settings.difficulty? {
+            Difficulty::Custom => Some(settings.custom.clamped().waves),
+            difficulty => self.get(difficulty).map(|script| script.waves.len() as u32),
+        }
+    }
+
+    pub fn intermission(&self, difficulty: Difficulty) -> f32 {
+        self.get(difficulty)
+            .map_or_else(default_intermission, |script| script.intermission)
+    }
+}
+
+// Endless waves are generated instead of scripted: more zombies, tougher and
+// faster ones, and a wider archetype mix the longer the run goes on
+pub fn endless_wave(wave: u32) -> WaveDefinition {
+    let level = wave.saturating_sub(1) as f32;
+    let count = 6 + wave * 3;
+    let batch = 1 + wave / 4;
+
+    let mut groups = vec![SpawnGroup {
+        archetype: ZombieArchetype::Walker,
+        count,
+        batch,
+        delay: 0.0,
+    }];
+    if wave >= 2 {
+        groups.push(SpawnGroup {
+            archetype: ZombieArchetype::Brute,
+            count: count / 3,
+            batch: batch.div_ceil(2),
+            delay: 4.0,
+        });
+    }
+    if wave >= 3 {
+        groups.push(SpawnGroup {
+            archetype: ZombieArchetype::Runner,
+            count: count / 4,
+            batch: batch.div_ceil(2),
+            delay: 8.0,
+        });
+    }
+    if wave >= 5 {
+        groups.push(SpawnGroup {
+            archetype: ZombieArchetype::Tank,
+            count: wave / 2,
+            batch: 1,
+            delay: 12.0,
+        });
+    }
+
+    // Every 5th wave ends with a boss
+    let boss = wave.is_multiple_of(5).then_some(BossSpawn {
+        archetype: ZombieArchetype::Boss,
+        delay: 20.0,
+    });
+
+    let start_interval = (2.0 * 0.95f32.powf(level)).max(0.6);
+
+    WaveDefinition {
+        spawn_interval: SpawnCurve {
+            start: start_interval,
+            end: start_interval * 0.7,
+        },
+        groups,
+        boss,
+        // Alternate between map spawn points and closing in from off-screen
+        spawn: if wave.is_multiple_of(2) {
+            SpawnStrategy::OffscreenRing
+        } else {
+            SpawnStrategy::SpawnPoints
+        },
+        min_player_distance: default_min_player_distance(),
+        health_multiplier: 1.0 + 0.12 * level,
+        speed_multiplier: (1.0 + 0.03 * level).min(1.6),
+        damage_multiplier: 1.0,
+        reward_multiplier: 1.0,
+    }
+}
+
+fn parse_script(name: &str, source: &str) -> WaveScript {
+    let script: WaveScript = ron::from_str(source)
+        .unwrap_or_else(|err| panic!("Invalid wave script '{}': {}", name, err));
+    assert!(
+        !script.waves.is_empty(),
+        "Wave script '{}' has no waves",
+        name
+    );
+    script
+}
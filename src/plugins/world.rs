@@ -1,28 +1,313 @@
+use crate::components::{Collider, Door, LevelGeometry, SpawnPoint, Wall};
+use crate::states::RunTeardown;
 use bevy::prelude::*;
+use std::collections::HashSet;
 
 pub struct WorldPlugin;
 
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_level);
+        app.init_resource::<UnlockedSections>()
+            .init_resource::<MapBounds>()
+            // Rebuild the map every time we go back to the menu so opened doors close again
+            .add_systems(RunTeardown, spawn_level);
     }
 }
 
-fn spawn_level(mut _commands: Commands) {
+// Section 0 is the starting area and is always open
+pub const START_SECTION: u32 = 0;
+
+#[derive(Resource)]
+pub struct UnlockedSections(pub HashSet<u32>);
+
+impl Default for UnlockedSections {
+    fn default() -> Self {
+        Self(HashSet::from([START_SECTION]))
+    }
+}
+
+impl UnlockedSections {
+    pub fn is_unlocked(&self, section: u32) -> bool {
+        self.0.contains(&section)
+    }
+}
+
+// Menu name of a section
+pub fn section_name(section: u32) -> &'static str {
+    match section {
+        1 => "West Wing",
+        2 => "East Wing",
+        _ => "Courtyard",
+    }
+}
+
+// Opens a wing before the run starts, as if its door had already been bought
+pub fn open_section(
+    commands: &mut Commands,
+    doors: &Query<(Entity, &Door)>,
+    unlocked: &mut UnlockedSections,
+    section: u32,
+) {
+    for (entity, door) in doors {
+        if door.section == section {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    unlocked.0.insert(section);
+}
+
+// Which section a point on the map belongs to, split by the two partitions
+pub fn section_at(position: Vec2) -> u32 {
+    if position.x < -PARTITION_X {
+        1
+    } else if position.x > PARTITION_X {
+        2
+    } else {
+        START_SECTION
+    }
+}
+
+// Playable area inside the outer walls
+#[derive(Resource)]
+pub struct MapBounds {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Default for MapBounds {
+    fn default() -> Self {
+        let inner = Vec2::new(MAP_HALF_WIDTH, MAP_HALF_HEIGHT) - WALL_THICKNESS / 2.0;
+        Self {
+            min: -inner,
+            max: inner,
+        }
+    }
+}
+
+impl MapBounds {
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    // Clamp a point so a box of `half_size` around it stays inside the map
+    pub fn clamp(&self, point: Vec2, half_size: Vec2) -> Vec2 {
+        point.clamp(self.min + half_size, self.max - half_size)
+    }
+
+    // Like clamp, but centers the axis instead when the view is bigger than the map
+    pub fn clamp_view(&self, point: Vec2, half_view: Vec2) -> Vec2 {
+        let center = (self.min + self.max) / 2.0;
+        let mut result = point;
+        for axis in 0..2 {
+            if half_view[axis] * 2.0 >= self.size()[axis] {
+                result[axis] = center[axis];
+            } else {
+                result[axis] = point[axis].clamp(
+                    self.min[axis] + half_view[axis],
+                    self.max[axis] - half_view[axis],
+                );
+            }
+        }
+        result
+    }
+}
+
+const WALL_THICKNESS: f32 = 20.0;
+const MAP_HALF_WIDTH: f32 = 1200.0;
+const MAP_HALF_HEIGHT: f32 = 700.0;
+const PARTITION_X: f32 = 400.0;
+
+fn spawn_level(
+    mut commands: Commands,
+    existing: Query<Entity, With<LevelGeometry>>,
+    mut unlocked: ResMut<UnlockedSections>,
+    mut bounds: ResMut<MapBounds>,
+) {
+    for entity in &existing {
+        commands.entity(entity).despawn_recursive();
+    }
+    *unlocked = UnlockedSections::default();
+    *bounds = MapBounds::default();
+
     // Wall Material (Grey)
-    let _wall_color = Color::srgb(0.5, 0.5, 0.5);
-    let _wall_size = Vec2::new(40.0, 40.0);
+    let wall_color = Color::srgb(0.5, 0.5, 0.5);
+
+    // Arena Layout (bigger than the window, the camera follows the player)
+    // | West Wing (1) | Courtyard (0) | East Wing (2) |
+    let half_w = MAP_HALF_WIDTH;
+    let half_h = MAP_HALF_HEIGHT;
+    let partition_x = PARTITION_X;
+    let door_height = 80.0;
+
+    // Outer boundary
+    spawn_wall(
+        &mut commands,
+        Vec2::new(0.0, half_h),
+        Vec2::new(half_w * 2.0, WALL_THICKNESS),
+        wall_color,
+    );
+    spawn_wall(
+        &mut commands,
+        Vec2::new(0.0, -half_h),
+        Vec2::new(half_w * 2.0, WALL_THICKNESS),
+        wall_color,
+    );
+    spawn_wall(
+        &mut commands,
+        Vec2::new(half_w, 0.0),
+        Vec2::new(WALL_THICKNESS, half_h * 2.0),
+        wall_color,
+    );
+    spawn_wall(
+        &mut commands,
+        Vec2::new(-half_w, 0.0),
+        Vec2::new(WALL_THICKNESS, half_h * 2.0),
+        wall_color,
+    );
+
+    // Partitions with a gap in the middle for the door
+    for x in [-partition_x, partition_x] {
+        let segment_height = half_h - door_height / 2.0;
+        let segment_y = door_height / 2.0 + segment_height / 2.0;
+        spawn_wall(
+            &mut commands,
+            Vec2::new(x, segment_y),
+            Vec2::new(WALL_THICKNESS, segment_height),
+            wall_color,
+        );
+        spawn_wall(
+            &mut commands,
+            Vec2::new(x, -segment_y),
+            Vec2::new(WALL_THICKNESS, segment_height),
+            wall_color,
+        );
+    }
 
-    // Simple Housing Complex Layout
-    // A few "Houses" (Rectangles of walls)
+    // Doors
+    spawn_door(
+        &mut commands,
+        Vec2::new(-partition_x, 0.0),
+        Vec2::new(WALL_THICKNESS, door_height),
+        50,
+        1,
+    );
+    spawn_door(
+        &mut commands,
+        Vec2::new(partition_x, 0.0),
+        Vec2::new(WALL_THICKNESS, door_height),
+        100,
+        2,
+    );
 
-    // House 1 (Top Left)
-    // spawn_wall_rect(&mut commands, Vec2::new(-200.0, 200.0), 100.0, 80.0, wall_color);
+    // Spawn Points
+    // Courtyard corners
+    spawn_point(
+        &mut commands,
+        "courtyard_nw",
+        Vec2::new(-330.0, 620.0),
+        START_SECTION,
+    );
+    spawn_point(
+        &mut commands,
+        "courtyard_ne",
+        Vec2::new(330.0, 620.0),
+        START_SECTION,
+    );
+    spawn_point(
+        &mut commands,
+        "courtyard_sw",
+        Vec2::new(-330.0, -620.0),
+        START_SECTION,
+    );
+    spawn_point(
+        &mut commands,
+        "courtyard_se",
+        Vec2::new(330.0, -620.0),
+        START_SECTION,
+    );
+    // West Wing
+    spawn_point(
+        &mut commands,
+        "west_wing_north",
+        Vec2::new(-800.0, 450.0),
+        1,
+    );
+    spawn_point(
+        &mut commands,
+        "west_wing_south",
+        Vec2::new(-800.0, -450.0),
+        1,
+    );
+    spawn_point(&mut commands, "west_wing_far", Vec2::new(-1100.0, 0.0), 1);
+    // East Wing
+    spawn_point(&mut commands, "east_wing_north", Vec2::new(800.0, 450.0), 2);
+    spawn_point(
+        &mut commands,
+        "east_wing_south",
+        Vec2::new(800.0, -450.0),
+        2,
+    );
+    spawn_point(&mut commands, "east_wing_far", Vec2::new(1100.0, 0.0), 2);
+}
 
-    // House 2 (Bottom Right)
-    // spawn_wall_rect(&mut commands, Vec2::new(200.0, -150.0), 120.0, 100.0, wall_color);
+fn spawn_wall(commands: &mut Commands, position: Vec2, size: Vec2, color: Color) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(0.0)),
+            ..default()
+        },
+        Wall,
+        Collider { size },
+        LevelGeometry,
+    ));
+}
+
+fn spawn_door(commands: &mut Commands, position: Vec2, size: Vec2, cost: u32, section: u32) {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.55, 0.35, 0.15), // Brown Door
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.0)),
+                ..default()
+            },
+            Wall,
+            Collider { size },
+            Door { cost, section },
+            LevelGeometry,
+        ))
+        .with_children(|parent| {
+            // Price tag floating above the door
+            parent.spawn(Text2dBundle {
+                text: Text::from_section(
+                    format!("[E] ${}", cost),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(1.0, 0.8, 0.0),
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, size.y / 2.0 + 12.0, 1.0),
+                ..default()
+            });
+        });
+}
 
-    // Some random debris/walls
-    // spawn_wall(&mut commands, Vec2::new(0.0, 50.0), wall_size, wall_color);
-    // spawn_wall(&mut commands, Vec2::new(50.0, -50.0), wall_size, wall_color);
+fn spawn_point(commands: &mut Commands, name: &str, position: Vec2, section: u32) {
+    commands.spawn((
+        TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+        SpawnPoint {
+            section,
+            name: name.to_string(),
+        },
+        LevelGeometry,
+    ));
 }
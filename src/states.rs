@@ -1,11 +1,83 @@
+use bevy::ecs::schedule::ScheduleLabel;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
 pub enum GameState {
     #[default]
     Dashboard,
+    // Online co-op: hosting or joined, waiting for the host to start
+    Lobby,
     Playing,
     Paused,
     GameOver,
     Win,
+    // Joined someone else's run, which only plays on their machine
+    Online,
+}
+
+// Phase of the current run, only exists while Playing
+#[derive(SubStates, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[source(GameState = GameState::Playing)]
+pub enum WavePhase {
+    #[default]
+    Combat,
+    Intermission,
+}
+
+// Which menu page is showing, only exists on the Dashboard
+#[derive(SubStates, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[source(GameState = GameState::Dashboard)]
+pub enum DashboardScreen {
+    #[default]
+    Main,
+    CustomDifficulty,
+    Replays,
+    Controls,
+}
+
+// Runs once as a run starts, from either the Dashboard or the Lobby, before
+// OnEnter(Playing). Anything that has to be fixed for the whole run goes here.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RunSetup;
+
+// Runs on the way back to the Dashboard or the Lobby and clears out the last run
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RunTeardown;
+
+// Registers the states above. Needs Bevy's StatesPlugin (part of DefaultPlugins).
+pub struct GameStatePlugin;
+
+impl Plugin for GameStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .add_sub_state::<WavePhase>()
+            .add_sub_state::<DashboardScreen>()
+            .init_schedule(RunSetup)
+            .init_schedule(RunTeardown)
+            .add_systems(
+                OnTransition {
+                    exited: GameState::Dashboard,
+                    entered: GameState::Playing,
+                },
+                run_setup,
+            )
+            .add_systems(
+                OnTransition {
+                    exited: GameState::Lobby,
+                    entered: GameState::Playing,
+                },
+                run_setup,
+            )
+            .add_systems(OnEnter(GameState::Dashboard), run_teardown)
+            .add_systems(OnEnter(GameState::Lobby), run_teardown);
+    }
+}
+
+fn run_setup(world: &mut World) {
+    world.run_schedule(RunSetup);
+}
+
+fn run_teardown(world: &mut World) {
+    world.run_schedule(RunTeardown);
 }
@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+// Small RON save files next to the game (or in $ZOMBIE_SAVE_DIR).
+// The browser build has no filesystem, so there loading finds nothing and saving is a no-op.
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_dir() -> PathBuf {
+    std::env::var_os("ZOMBIE_SAVE_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("saves"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let path = save_dir().join(format!("{}.ron", name));
+    let contents = std::fs::read_to_string(&path).ok()?;
+    match ron::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            warn!("Ignoring corrupt save file {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    let dir = save_dir();
+    let path = dir.join(format!("{}.ron", name));
+    let result = std::fs::create_dir_all(&dir).and_then(|_| {
+        let contents = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+            .map_err(std::io::Error::other)?;
+        std::fs::write(&path, contents)
+    });
+
+    if let Err(err) = result {
+        warn!("Failed to save {}: {}", path.display(), err);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load<T: DeserializeOwned>(_name: &str) -> Option<T> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save<T: Serialize>(_name: &str, _value: &T) {}
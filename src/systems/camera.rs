@@ -1,6 +1,98 @@
+use crate::plugins::player::Player;
+use crate::plugins::world::MapBounds;
 use bevy::prelude::*;
 
+#[derive(Component)]
+pub struct MainCamera;
+
+// The window's size at normal zoom. Gameplay that cares about what's on
+// screen uses this instead of the live projection, so it doesn't change with
+// the window, the zoom pulse or the shake.
+pub const VIEW_SIZE: Vec2 = Vec2::new(1280.0, 720.0);
+
+// Where the camera wants to be before any effects are applied on top
+#[derive(Component, Default)]
+pub struct CameraRig {
+    pub focus: Vec2,
+}
+
+#[derive(Resource)]
+pub struct CameraFollow {
+    // Higher = snappier. Roughly "how many times per second we close the gap"
+    pub smoothing: f32,
+    // Fraction of the cursor offset (from screen center) to lead the player by
+    pub look_ahead: f32,
+    pub max_look_ahead: f32,
+}
+
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self {
+            smoothing: 8.0,
+            look_ahead: 0.25,
+            max_look_ahead: 120.0,
+        }
+    }
+}
+
 pub fn setup_camera(mut commands: Commands) {
     // 2D orthographic camera
-    commands.spawn(Camera2dBundle::default());
+    commands.spawn((Camera2dBundle::default(), MainCamera, CameraRig::default()));
+}
+
+pub fn camera_follow(
+    windows: Query<&Window>,
+    player_query: Query<&Transform, (With<Player>, Without<MainCamera>)>,
+    mut camera_query: Query<
+        (&mut Transform, &mut CameraRig, &OrthographicProjection),
+        With<MainCamera>,
+    >,
+    follow: Res<CameraFollow>,
+    bounds: Res<MapBounds>,
+    time: Res<Time>,
+) {
+    let players: Vec<Vec2> = player_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    if players.is_empty() {
+        return;
+    }
+    let Ok((mut transform, mut rig, projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    // Co-op shares one screen, centred on the whole group
+    let mut target = players.iter().sum::<Vec2>() / players.len() as f32;
+
+    // Look-ahead: lean toward the cursor so the player sees more of where they aim.
+    // Only when playing alone, the cursor belongs to one of several players otherwise.
+    if players.len() == 1
+        && let Ok(window) = windows.get_single()
+        && let Some(cursor) = window.cursor_position()
+    {
+        let from_center = cursor - window.size() / 2.0;
+        // Screen Y grows downward, world Y grows upward
+        let offset = Vec2::new(from_center.x, -from_center.y) * projection.scale;
+        target += (offset * follow.look_ahead).clamp_length_max(follow.max_look_ahead);
+    }
+
+    // Frame-rate independent exponential smoothing
+    let t = 1.0 - (-follow.smoothing * time.delta_seconds()).exp();
+    rig.focus = rig.focus.lerp(target, t);
+
+    // Keep the view inside the map (or centered on it if the map is smaller than the view)
+    let half_view = projection.area.size() / 2.0;
+    rig.focus = bounds.clamp_view(rig.focus, half_view);
+
+    transform.translation.x = rig.focus.x;
+    transform.translation.y = rig.focus.y;
+}
+
+pub fn reset_camera(mut camera_query: Query<(&mut Transform, &mut CameraRig), With<MainCamera>>) {
+    for (mut transform, mut rig) in &mut camera_query {
+        rig.focus = Vec2::ZERO;
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+    }
 }
@@ -0,0 +1,123 @@
+mod support;
+
+use bevy::prelude::*;
+use support::TestApp;
+use zombie::components::{Health, Zombie};
+use zombie::plugins::combat::CombatPlugin;
+use zombie::plugins::controls::ControlsPlugin;
+use zombie::plugins::enemy::ZombieArchetype;
+use zombie::plugins::game_mode::{GameMode, GameModePlugin};
+use zombie::plugins::player::{Downed, Player, PlayerId, PlayerPlugin};
+use zombie::plugins::run_modifiers::RunModifiersPlugin;
+use zombie::plugins::settings::{Difficulty, GameSettings, SettingsPlugin};
+use zombie::plugins::shop::ShopPlugin;
+use zombie::plugins::simulation::SimulationPlugin;
+use zombie::plugins::wave::WaveState;
+use zombie::plugins::world::WorldPlugin;
+use zombie::states::GameState;
+
+// Players, input and damage, but no waves or zombie AI
+fn coop_app(extra_players: usize) -> TestApp {
+    let mut app = TestApp::with_plugins((
+        PlayerPlugin,
+        CombatPlugin,
+        ControlsPlugin,
+        SettingsPlugin,
+        WorldPlugin,
+        RunModifiersPlugin,
+        ShopPlugin,
+        SimulationPlugin,
+        GameModePlugin,
+    ));
+    // The game mode rules read the wave count
+    app.world_mut().init_resource::<WaveState>();
+    app.world_mut().resource_mut::<GameSettings>().extra_players = extra_players;
+    app
+}
+
+fn player_with_id(app: &mut TestApp, index: usize) -> Entity {
+    app.world_mut()
+        .query::<(Entity, &PlayerId)>()
+        .iter(app.app.world())
+        .find(|(_, id)| id.0 == index)
+        .map(|(entity, _)| entity)
+        .expect("No such player")
+}
+
+fn position(app: &TestApp, player: Entity) -> Vec2 {
+    app.world()
+        .get::<Transform>(player)
+        .unwrap()
+        .translation
+        .truncate()
+}
+
+#[test]
+fn keyboard_drives_player_one_and_a_pad_drives_player_two() {
+    let mut app = coop_app(1);
+    let gamepad = app.connect_gamepad();
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    assert_eq!(app.count::<Player>(), 2);
+    let first = player_with_id(&mut app, 0);
+    let second = player_with_id(&mut app, 1);
+    let first_start = position(&app, first);
+    let second_start = position(&app, second);
+
+    app.press_key(KeyCode::KeyD);
+    app.move_stick(gamepad, GamepadAxisType::LeftStickY, 1.0);
+    app.advance(1.0);
+
+    let first_moved = position(&app, first) - first_start;
+    let second_moved = position(&app, second) - second_start;
+    assert!(
+        first_moved.x > 100.0 && first_moved.y.abs() < 1.0,
+        "P1 moved {:?}",
+        first_moved
+    );
+    assert!(
+        second_moved.y > 100.0 && second_moved.x.abs() < 1.0,
+        "P2 moved {:?}",
+        second_moved
+    );
+}
+
+#[test]
+fn a_downed_player_is_revived_by_a_teammate() {
+    let mut app = coop_app(1);
+    let gamepad = app.connect_gamepad();
+    let first = app.spawn_coop_player(0, Vec2::ZERO);
+    let second = app.spawn_coop_player(1, Vec2::new(200.0, 0.0));
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+
+    // 100 health against 10 damage a second, the other player is out of reach
+    let zombie = app.spawn_zombie(ZombieArchetype::Walker, Vec2::ZERO);
+    assert!(app.advance_until(15.0, |app| app.world().get::<Downed>(first).is_some()));
+    assert!(app.world().get::<Downed>(second).is_none());
+    app.world_mut().entity_mut(zombie).despawn();
+
+    // One player down isn't the end of the run
+    app.advance(1.0);
+    assert_eq!(app.state(), GameState::Playing);
+    assert_eq!(app.count::<Zombie>(), 0);
+
+    // Walk over and stand next to them
+    app.move_stick(gamepad, GamepadAxisType::LeftStickX, -1.0);
+    assert!(app.advance_until(3.0, |app| position(app, second).x < 30.0));
+    app.move_stick(gamepad, GamepadAxisType::LeftStickX, 0.0);
+    assert!(app.advance_until(5.0, |app| app.world().get::<Downed>(first).is_none()));
+
+    let health = app.world().get::<Health>(first).unwrap();
+    assert_eq!(health.current, health.max / 2.0);
+}
+
+#[test]
+fn the_run_ends_once_every_player_is_down() {
+    let mut app = coop_app(1);
+    app.spawn_coop_player(0, Vec2::ZERO);
+    app.spawn_coop_player(1, Vec2::new(5.0, 0.0));
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+
+    app.spawn_zombie(ZombieArchetype::Walker, Vec2::ZERO);
+    assert!(app.advance_until_state(GameState::GameOver, 15.0));
+    assert_eq!(app.count::<Player>(), 0);
+}
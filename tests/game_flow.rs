@@ -0,0 +1,185 @@
+mod support;
+
+use bevy::ecs::schedule::{LogLevel, ScheduleBuildSettings};
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use support::TestApp;
+use zombie::components::{Health, Zombie};
+use zombie::headless::gameplay_app;
+use zombie::plugins::bot::{Bot, BotPlugin};
+use zombie::plugins::daily_challenge::{DailyChallenge, DailyChallengePlugin};
+use zombie::plugins::enemy::ZombieArchetype;
+use zombie::plugins::game_mode::GameMode;
+use zombie::plugins::pause::PausePlugin;
+use zombie::plugins::player::{Loadout, Player};
+use zombie::plugins::progression::GlobalPlayerStats;
+use zombie::plugins::settings::{CustomDifficulty, Difficulty, GameSettings};
+use zombie::plugins::spawning::{SpawnContext, SpawnStrategy};
+use zombie::plugins::wave::WaveState;
+use zombie::plugins::world::{START_SECTION, section_at};
+use zombie::states::{GameState, WavePhase};
+
+#[test]
+fn starting_a_run_spawns_the_player_and_the_first_wave() {
+    let mut app = TestApp::gameplay();
+    assert_eq!(app.state(), GameState::Dashboard);
+
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    assert_eq!(app.wave_phase(), Some(WavePhase::Combat));
+    assert_eq!(app.count::<Player>(), 1);
+
+    assert!(app.advance_until(10.0, |app| app.count::<Zombie>() > 0));
+}
+
+// Ticks have to play out the same way every time for seeds and replays, so
+// no two gameplay systems may be left for the executor to order
+#[test]
+fn every_gameplay_system_has_a_fixed_order() {
+    let mut app = gameplay_app();
+    app.edit_schedule(FixedUpdate, |schedule| {
+        schedule.set_build_settings(ScheduleBuildSettings {
+            ambiguity_detection: LogLevel::Error,
+            ..default()
+        });
+    });
+    let mut app = TestApp::build(app);
+
+    // The schedule is only built, and checked, once it first runs
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    app.advance(0.1);
+}
+
+#[test]
+fn zombie_on_top_of_the_player_ends_the_run() {
+    let mut app = TestApp::gameplay();
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+
+    let position = app.player_position();
+    app.spawn_zombie(ZombieArchetype::Walker, position);
+
+    // 100 health against 10 damage a second
+    app.advance(5.0);
+    let player = app.player();
+    let health = app.world().get::<Health>(player).unwrap().current;
+    assert!(health < 100.0, "player took no damage");
+
+    assert!(app.advance_until_state(GameState::GameOver, 15.0));
+    assert_eq!(app.count::<Player>(), 0);
+}
+
+#[test]
+fn clearing_every_wave_wins() {
+    let mut game = gameplay_app();
+    game.add_plugins(BotPlugin)
+        .insert_resource(Bot { enabled: true });
+    let mut app = TestApp::build(game);
+
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    assert!(app.advance_until_state(GameState::Win, 120.0));
+}
+
+#[test]
+fn zombies_never_spawn_behind_closed_doors() {
+    let mut app = TestApp::gameplay();
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+
+    let positions = app.world_mut().run_system_once(|mut spawns: SpawnContext| {
+        let mut positions = Vec::new();
+        for strategy in [SpawnStrategy::OffscreenRing, SpawnStrategy::Anywhere] {
+            for _ in 0..50 {
+                positions.push(spawns.pick_position(&strategy, 300.0, 40.0));
+            }
+        }
+        positions
+    });
+    assert!(
+        positions
+            .iter()
+            .all(|position| section_at(*position) == START_SECTION)
+    );
+}
+
+#[test]
+fn a_custom_difficulty_outside_the_limits_is_clamped() {
+    let mut app = TestApp::gameplay();
+    app.world_mut().resource_mut::<GameSettings>().custom = CustomDifficulty {
+        waves: 0,
+        spawn_rate: 0.0,
+        ..CustomDifficulty::STANDARD
+    };
+
+    app.start_run(Difficulty::Custom, GameMode::ClearWaves);
+    app.advance(2.0);
+    assert_eq!(app.state(), GameState::Playing);
+    let waves = app.world().resource::<WaveState>();
+    assert_eq!((waves.current_wave, waves.total_waves), (1, Some(1)));
+}
+
+// P and ESC are handled by the pause menu
+fn app_with_pause_menu() -> TestApp {
+    let mut game = gameplay_app();
+    game.add_plugins(PausePlugin);
+    TestApp::build(game)
+}
+
+#[test]
+fn pausing_keeps_the_run_going_where_it_left_off() {
+    let mut app = app_with_pause_menu();
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    let player = app.player();
+
+    app.tap_key(KeyCode::KeyP);
+    assert_eq!(app.state(), GameState::Paused);
+    assert_eq!(app.wave_phase(), None);
+
+    app.tap_key(KeyCode::KeyP);
+    assert_eq!(app.state(), GameState::Playing);
+    assert_eq!(app.player(), player);
+}
+
+#[test]
+fn escape_goes_back_to_the_dashboard_and_clears_the_level() {
+    let mut app = app_with_pause_menu();
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    app.spawn_zombie(ZombieArchetype::Walker, Vec2::new(200.0, 0.0));
+
+    app.tap_key(KeyCode::Escape);
+    assert_eq!(app.state(), GameState::Dashboard);
+    assert_eq!(app.count::<Player>(), 0);
+    assert_eq!(app.count::<Zombie>(), 0);
+}
+
+#[test]
+fn quitting_an_endless_run_still_records_the_best_wave() {
+    let mut app = app_with_pause_menu();
+    app.start_run(Difficulty::Endless, GameMode::ClearWaves);
+    app.advance(1.0);
+
+    app.tap_key(KeyCode::Escape);
+    assert_eq!(app.state(), GameState::Dashboard);
+    let stats = app.world().resource::<GlobalPlayerStats>();
+    assert_eq!(stats.best_endless_wave, 1);
+}
+
+#[test]
+fn the_daily_challenge_plays_without_unlocks_and_leaves_the_mode_alone() {
+    let mut game = gameplay_app();
+    game.add_plugins((PausePlugin, DailyChallengePlugin));
+    let mut app = TestApp::build(game);
+
+    let world = app.world_mut();
+    let mut stats = world.resource_mut::<GlobalPlayerStats>();
+    stats.weapon_upgrade_level = 3;
+    stats.unlocked_rifle = true;
+    world.resource_mut::<GameSettings>().daily = Some(DailyChallenge::for_day(20_000));
+
+    app.start_run(Difficulty::Hard, GameMode::Survival);
+    assert_eq!(*app.world().resource::<Loadout>(), Loadout::default());
+    let settings = app.world().resource::<GameSettings>();
+    assert_eq!(settings.game_mode, GameMode::ClearWaves);
+
+    app.tap_key(KeyCode::Escape);
+    let settings = app.world().resource::<GameSettings>();
+    assert_eq!(settings.game_mode, GameMode::Survival);
+    assert!(settings.daily.is_none());
+}
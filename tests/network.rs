@@ -0,0 +1,258 @@
+mod support;
+
+use bevy::prelude::*;
+use std::net::{Ipv4Addr, SocketAddr};
+use support::TestApp;
+use zombie::components::Door;
+use zombie::headless::gameplay_app;
+use zombie::plugins::controls::ControlsPlugin;
+use zombie::plugins::game_mode::GameMode;
+use zombie::plugins::net_client::{ConnectionStatus, NetClient, NetClientPlugin};
+use zombie::plugins::net_server::{NetServer, NetServerPlugin};
+use zombie::plugins::player::{Player, PlayerId};
+use zombie::plugins::settings::{Difficulty, GameSettings, SettingsPlugin};
+use zombie::plugins::world::{UnlockedSections, WorldPlugin};
+use zombie::states::GameState;
+
+// A server on a free localhost port, with `local_players` of its own
+fn server_with(local_players: usize) -> TestApp {
+    let mut game = gameplay_app();
+    game.add_plugins(NetServerPlugin)
+        .insert_resource(NetServer::bind((Ipv4Addr::LOCALHOST, 0).into(), local_players).unwrap());
+    let mut settings = game.world_mut().resource_mut::<GameSettings>();
+    settings.difficulty = Some(Difficulty::Easy);
+    settings.seed = Some(1);
+    TestApp::build(game)
+}
+
+fn server() -> TestApp {
+    server_with(0)
+}
+
+// Hosting from the lobby: P1 plays on the server's machine
+fn host() -> TestApp {
+    let mut host = server_with(1);
+    host.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Lobby);
+    host.update();
+    host
+}
+
+fn client(server: &TestApp) -> TestApp {
+    let address: SocketAddr = server.world().resource::<NetServer>().local_addr();
+    let mut client =
+        TestApp::with_plugins((SettingsPlugin, ControlsPlugin, WorldPlugin, NetClientPlugin));
+    client
+        .world_mut()
+        .insert_resource(NetClient::connect(address).unwrap());
+    client
+}
+
+fn chat(app: &TestApp) -> Vec<String> {
+    let lines = match app.world().get_resource::<NetServer>() {
+        Some(server) => server.chat().to_vec(),
+        None => app
+            .world()
+            .resource::<NetClient>()
+            .session()
+            .unwrap()
+            .chat
+            .clone(),
+    };
+    lines.iter().map(|line| line.describe()).collect()
+}
+
+// Both sides take turns, one frame each
+fn step(server: &mut TestApp, clients: &mut [&mut TestApp], frames: u32) {
+    for _ in 0..frames {
+        server.update();
+        for client in clients.iter_mut() {
+            client.update();
+        }
+    }
+}
+
+fn server_players(server: &mut TestApp) -> Vec<(usize, Vec2)> {
+    let mut players: Vec<(usize, Vec2)> = server
+        .world_mut()
+        .query_filtered::<(&PlayerId, &Transform), With<Player>>()
+        .iter(server.app.world())
+        .map(|(id, transform)| (id.0, transform.translation.truncate()))
+        .collect();
+    players.sort_by_key(|(id, _)| *id);
+    players
+}
+
+#[test]
+fn joining_starts_a_run_the_client_can_see() {
+    let mut server = server();
+    let mut client = client(&server);
+    step(&mut server, &mut [&mut client], 30);
+
+    assert_eq!(server.state(), GameState::Playing);
+    assert_eq!(server_players(&mut server).len(), 1);
+
+    let net = client.world().resource::<NetClient>();
+    assert_eq!(net.status, ConnectionStatus::Connected);
+    assert_eq!(net.player, Some(0));
+    assert_eq!(client.state(), GameState::Online);
+    // Our own player, driven by the local controls
+    assert_eq!(client.count::<PlayerId>(), 1);
+}
+
+#[test]
+fn client_input_moves_its_player_on_the_server() {
+    let mut server = server();
+    let mut client = client(&server);
+    step(&mut server, &mut [&mut client], 30);
+    let start = server_players(&mut server)[0].1;
+
+    client.press_key(KeyCode::KeyD);
+    step(&mut server, &mut [&mut client], 60);
+
+    // 150 units a second on the server, and the client draws it there too
+    let moved = server_players(&mut server)[0].1 - start;
+    assert!(moved.x > 100.0 && moved.y.abs() < 1.0, "moved {:?}", moved);
+    let drawn = client.player_position();
+    assert!(drawn.x > start.x + 80.0, "client shows {:?}", drawn);
+}
+
+#[test]
+fn a_client_leaving_mid_run_only_removes_their_player() {
+    let mut server = server();
+    let mut first = client(&server);
+    let mut second = client(&server);
+    step(&mut server, &mut [&mut first, &mut second], 30);
+    assert_eq!(
+        server_players(&mut server)
+            .iter()
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+
+    second.world_mut().send_event(AppExit::Success);
+    step(&mut server, &mut [&mut first, &mut second], 10);
+
+    assert_eq!(server.state(), GameState::Playing);
+    let players = server_players(&mut server);
+    assert_eq!(players.len(), 1);
+    assert_eq!(players[0].0, 0);
+    assert_eq!(chat(&first).last().unwrap(), "* P2 left");
+}
+
+#[test]
+fn a_hosted_run_starts_once_everyone_is_ready() {
+    let mut host = host();
+    let mut guest = client(&host);
+    step(&mut host, &mut [&mut guest], 10);
+
+    // Waiting in the lobby, as the second player
+    assert_eq!(guest.state(), GameState::Lobby);
+    assert_eq!(guest.world().resource::<NetClient>().player, Some(1));
+    assert!(!host.world().resource::<NetServer>().all_ready());
+
+    // The host's picks show up for the guest
+    host.world_mut().resource_mut::<GameSettings>().game_mode = GameMode::Survival;
+    guest.world_mut().resource_mut::<NetClient>().ready = true;
+    step(&mut host, &mut [&mut guest], 30);
+    assert!(host.world().resource::<NetServer>().all_ready());
+    let session = guest.world().resource::<NetClient>().session().unwrap();
+    assert_eq!(session.game_mode, GameMode::Survival);
+    assert_eq!(session.difficulty, Some(Difficulty::Easy));
+
+    // What the Start button does
+    host.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+    step(&mut host, &mut [&mut guest], 10);
+
+    assert_eq!(host.state(), GameState::Playing);
+    assert_eq!(
+        server_players(&mut host)
+            .iter()
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+    // Watching, not simulating its own copy of the run
+    assert_eq!(guest.state(), GameState::Online);
+    assert!(!guest.world().resource::<NetClient>().ready);
+}
+
+fn doors(app: &mut TestApp) -> Vec<u32> {
+    let world = app.world_mut();
+    world
+        .query::<&Door>()
+        .iter(world)
+        .map(|door| door.section)
+        .collect()
+}
+
+#[test]
+fn the_hosts_layout_opens_a_wing_for_everyone() {
+    let mut host = host();
+    let mut guest = client(&host);
+    step(&mut host, &mut [&mut guest], 10);
+
+    host.world_mut().resource_mut::<GameSettings>().open_section = Some(2);
+    guest.world_mut().resource_mut::<NetClient>().ready = true;
+    step(&mut host, &mut [&mut guest], 30);
+    let session = guest.world().resource::<NetClient>().session().unwrap();
+    assert_eq!(session.open_section, Some(2));
+
+    host.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+    step(&mut host, &mut [&mut guest], 10);
+
+    // Only the West Wing door is left to buy, on both sides
+    assert!(host.world().resource::<UnlockedSections>().is_unlocked(2));
+    assert_eq!(doors(&mut host), vec![1]);
+    assert_eq!(guest.state(), GameState::Online);
+    assert_eq!(doors(&mut guest), vec![1]);
+}
+
+#[test]
+fn chat_reaches_everyone_in_the_session() {
+    let mut host = host();
+    let mut guest = client(&host);
+    step(&mut host, &mut [&mut guest], 10);
+
+    guest.world_mut().resource_mut::<NetClient>().say("hello");
+    step(&mut host, &mut [&mut guest], 5);
+    host.world_mut()
+        .resource_mut::<NetServer>()
+        .say(Some(0), "  hi there ");
+    step(&mut host, &mut [&mut guest], 30);
+
+    let expected = vec!["* P2 joined", "P2: hello", "P1: hi there"];
+    assert_eq!(chat(&host), expected);
+    assert_eq!(chat(&guest), expected);
+}
+
+#[test]
+fn the_host_leaving_ends_the_session() {
+    let mut host = host();
+    let mut guest = client(&host);
+    step(&mut host, &mut [&mut guest], 10);
+    host.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+    step(&mut host, &mut [&mut guest], 10);
+    assert_eq!(guest.state(), GameState::Online);
+
+    // What Leave does on the host's side
+    host.world().resource::<NetServer>().close();
+    host.world_mut().remove_resource::<NetServer>();
+    step(&mut host, &mut [&mut guest], 5);
+
+    assert_eq!(
+        guest.world().resource::<NetClient>().status,
+        ConnectionStatus::Closed
+    );
+    // Back to the lobby to say so, with nothing left on screen
+    assert_eq!(guest.state(), GameState::Lobby);
+    assert_eq!(guest.count::<Player>(), 0);
+}
@@ -0,0 +1,256 @@
+mod support;
+
+use bevy::app::Plugins;
+use bevy::prelude::*;
+use support::TestApp;
+use zombie::components::Health;
+use zombie::plugins::combat::CombatPlugin;
+use zombie::plugins::controls::{Action, Binding, ControlsPlugin, Keybindings};
+use zombie::plugins::enemy::ZombieArchetype;
+use zombie::plugins::game_mode::{GameMode, GameModePlugin};
+use zombie::plugins::player::{PlayerPlugin, WeaponStats, WeaponType};
+use zombie::plugins::progression::GlobalPlayerStats;
+use zombie::plugins::run_modifiers::RunModifiersPlugin;
+use zombie::plugins::settings::{Difficulty, SettingsPlugin};
+use zombie::plugins::shop::{ShopPlugin, Wallet};
+use zombie::plugins::simulation::SimulationPlugin;
+use zombie::plugins::touch::{TouchControls, TouchControlsPlugin};
+use zombie::plugins::wave::WaveState;
+use zombie::plugins::world::WorldPlugin;
+
+// Just enough to walk around and shoot, no waves or zombie AI
+fn player_app() -> TestApp {
+    player_app_with(())
+}
+
+fn player_app_with<M>(extra: impl Plugins<M>) -> TestApp {
+    let mut app = TestApp::with_plugins((
+        (
+            PlayerPlugin,
+            CombatPlugin,
+            ControlsPlugin,
+            SettingsPlugin,
+            WorldPlugin,
+            RunModifiersPlugin,
+            ShopPlugin,
+            SimulationPlugin,
+            GameModePlugin,
+        ),
+        extra,
+    ));
+    // The game mode rules read the wave count
+    app.world_mut().init_resource::<WaveState>();
+    app
+}
+
+#[test]
+fn holding_a_movement_key_walks_the_player() {
+    let mut app = player_app();
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    let start = app.player_position();
+
+    app.press_key(KeyCode::KeyD);
+    app.advance(1.0);
+    app.release_key(KeyCode::KeyD);
+    app.update();
+    let moved = app.player_position() - start;
+
+    // 150 units a second
+    assert!((moved.x - 150.0).abs() < 5.0, "moved {:?}", moved);
+    assert_eq!(moved.y, 0.0);
+
+    app.advance(0.5);
+    assert!((app.player_position() - start - moved).length() < 0.01);
+}
+
+#[test]
+fn shooting_kills_a_zombie_in_front_of_the_player() {
+    let mut app = player_app();
+    app.spawn_player(Vec2::ZERO);
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    let zombie = app.spawn_zombie(ZombieArchetype::Walker, Vec2::new(150.0, 0.0));
+
+    app.press_mouse(MouseButton::Left);
+    assert!(app.advance_until(3.0, |app| app.world().get_entity(zombie).is_none()));
+    app.release_mouse(MouseButton::Left);
+
+    assert_eq!(app.world().resource::<Wallet>().money, 10);
+    let player = app.player();
+    assert!(app.world().get::<WeaponStats>(player).unwrap().current_ammo < 30);
+}
+
+#[test]
+fn walls_stop_bullets() {
+    let mut app = player_app();
+    app.spawn_player(Vec2::ZERO);
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    app.spawn_wall(Vec2::new(75.0, 0.0), Vec2::new(20.0, 200.0));
+    let zombie = app.spawn_zombie(ZombieArchetype::Walker, Vec2::new(150.0, 0.0));
+
+    app.press_mouse(MouseButton::Left);
+    app.advance(2.0);
+
+    let health = app.world().get::<Health>(zombie).unwrap();
+    assert_eq!(health.current, health.max);
+}
+
+#[test]
+fn fire_rate_can_be_bought_mid_wave_but_medkits_cannot() {
+    let mut app = player_app();
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    app.world_mut().resource_mut::<Wallet>().money = 100;
+    let player = app.player();
+    app.world_mut().get_mut::<Health>(player).unwrap().current = 50.0;
+    let fire_rate = |app: &TestApp| app.world().get::<WeaponStats>(player).unwrap().fire_rate;
+    let before = fire_rate(&app);
+
+    app.tap_key(KeyCode::KeyB);
+    assert_eq!(app.world().resource::<Wallet>().money, 90);
+    assert!(fire_rate(&app) < before);
+
+    app.tap_key(KeyCode::KeyH);
+    assert_eq!(app.world().resource::<Wallet>().money, 90);
+    assert_eq!(app.world().get::<Health>(player).unwrap().current, 50.0);
+}
+
+#[test]
+fn only_unlocked_weapons_can_be_picked() {
+    let mut app = player_app();
+    app.world_mut()
+        .resource_mut::<GlobalPlayerStats>()
+        .unlocked_rifle = true;
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    let player = app.player();
+    let weapon = |app: &TestApp| {
+        app.world()
+            .get::<WeaponStats>(player)
+            .unwrap()
+            .current_weapon
+    };
+
+    app.tap_key(KeyCode::Digit2);
+    assert_eq!(weapon(&app), WeaponType::Pistol);
+
+    app.tap_key(KeyCode::Digit3);
+    assert_eq!(weapon(&app), WeaponType::Rifle);
+
+    app.tap_key(KeyCode::Digit1);
+    assert_eq!(weapon(&app), WeaponType::Pistol);
+}
+
+#[test]
+fn rebound_keys_replace_the_defaults() {
+    let mut app = player_app();
+    let mut bindings = app.world_mut().resource_mut::<Keybindings>();
+    assert_eq!(
+        bindings.conflict(Binding::Key(KeyCode::KeyE), Action::MoveRight),
+        Some(Action::Interact)
+    );
+    bindings.rebind(Action::MoveRight, Binding::Key(KeyCode::ArrowRight));
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    let start = app.player_position();
+
+    app.press_key(KeyCode::KeyD);
+    app.advance(0.5);
+    app.release_key(KeyCode::KeyD);
+    assert_eq!(app.player_position(), start);
+
+    app.press_key(KeyCode::ArrowRight);
+    app.advance(0.5);
+    assert!(app.player_position().x > start.x);
+}
+
+#[test]
+fn left_stick_walks_at_the_speed_it_is_pushed() {
+    let mut app = player_app();
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    let pad = app.connect_gamepad();
+    let start = app.player_position();
+
+    app.move_stick(pad, GamepadAxisType::LeftStickY, 0.5);
+    app.advance(1.0);
+    let moved = app.player_position() - start;
+
+    // Half of 150 units a second
+    assert!((moved.y - 75.0).abs() < 5.0, "moved {:?}", moved);
+    assert!(moved.x.abs() < 0.01);
+}
+
+#[test]
+fn right_stick_aim_is_pulled_onto_a_nearby_zombie() {
+    let mut app = player_app();
+    app.spawn_player(Vec2::ZERO);
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    let pad = app.connect_gamepad();
+    // About 11 degrees off where the stick points
+    let zombie = app.spawn_zombie(ZombieArchetype::Walker, Vec2::new(150.0, 30.0));
+
+    app.move_stick(pad, GamepadAxisType::RightStickX, 1.0);
+    app.press_pad(pad, GamepadButtonType::RightTrigger2);
+    assert!(app.advance_until(3.0, |app| app.world().get_entity(zombie).is_none()));
+}
+
+#[test]
+fn next_weapon_skips_locked_weapons() {
+    let mut app = player_app();
+    app.world_mut()
+        .resource_mut::<GlobalPlayerStats>()
+        .unlocked_shotgun = true;
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    let pad = app.connect_gamepad();
+    let player = app.player();
+    let weapon = |app: &TestApp| {
+        app.world()
+            .get::<WeaponStats>(player)
+            .unwrap()
+            .current_weapon
+    };
+
+    app.tap_pad(pad, GamepadButtonType::North);
+    assert_eq!(weapon(&app), WeaponType::Shotgun);
+
+    app.tap_pad(pad, GamepadButtonType::North);
+    assert_eq!(weapon(&app), WeaponType::Pistol);
+}
+
+#[test]
+fn dragging_on_the_left_half_of_the_screen_walks_the_player() {
+    let mut app = player_app_with(TouchControlsPlugin);
+    // The halves of the screen come from the window size
+    app.world_mut().spawn(Window::default());
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    let start = app.player_position();
+
+    app.touch_start(0, Vec2::new(200.0, 500.0));
+    app.update();
+    assert!(app.world().resource::<TouchControls>().active);
+    app.touch_move(0, Vec2::new(300.0, 500.0));
+    app.advance(1.0);
+    app.touch_end(0, Vec2::new(300.0, 500.0));
+    app.update();
+    let moved = app.player_position() - start;
+
+    // Dragged past the edge of the stick, so full speed to the right
+    assert!((moved.x - 150.0).abs() < 5.0, "moved {:?}", moved);
+    assert!(moved.y.abs() < 0.01);
+
+    app.press_key(KeyCode::KeyW);
+    app.update();
+    assert!(!app.world().resource::<TouchControls>().active);
+}
+
+#[test]
+fn dragging_on_the_right_half_of_the_screen_aims_and_fires() {
+    let mut app = player_app_with(TouchControlsPlugin);
+    app.world_mut().spawn(Window::default());
+    app.spawn_player(Vec2::ZERO);
+    app.start_run(Difficulty::Easy, GameMode::ClearWaves);
+    // Straight up from the player, in world space
+    let zombie = app.spawn_zombie(ZombieArchetype::Walker, Vec2::new(0.0, 150.0));
+
+    // Up the screen is up the world
+    app.touch_start(0, Vec2::new(1000.0, 500.0));
+    app.update();
+    app.touch_move(0, Vec2::new(1000.0, 400.0));
+    assert!(app.advance_until(3.0, |app| app.world().get_entity(zombie).is_none()));
+}
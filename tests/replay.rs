@@ -0,0 +1,108 @@
+mod support;
+
+use bevy::prelude::*;
+use support::TestApp;
+use zombie::components::{Health, Zombie};
+use zombie::headless::gameplay_app;
+use zombie::plugins::bot::{Bot, BotPlugin};
+use zombie::plugins::game_mode::{GameMode, ModeState};
+use zombie::plugins::player::Player;
+use zombie::plugins::replay::{Replay, ReplayLibrary, ReplayPlugin};
+use zombie::plugins::settings::Difficulty;
+use zombie::plugins::shop::Wallet;
+use zombie::plugins::wave::WaveState;
+use zombie::states::{DashboardScreen, GameState};
+
+// Everything a replay has to reproduce, taken at the end of the recorded frames
+#[derive(Debug, PartialEq)]
+struct RunSnapshot {
+    elapsed: f32,
+    wave: u32,
+    money: u32,
+    players: Vec<(Vec3, f32)>,
+    zombies: Vec<(Vec3, f32)>,
+}
+
+fn snapshot(app: &mut TestApp) -> RunSnapshot {
+    let world = app.world_mut();
+    let players = world
+        .query_filtered::<(&Transform, &Health), With<Player>>()
+        .iter(world)
+        .map(|(transform, health)| (transform.translation, health.current))
+        .collect();
+    let mut zombies: Vec<(Vec3, f32)> = world
+        .query_filtered::<(&Transform, &Health), With<Zombie>>()
+        .iter(world)
+        .map(|(transform, health)| (transform.translation, health.current))
+        .collect();
+    zombies.sort_by(|a, b| a.0.to_array().partial_cmp(&b.0.to_array()).unwrap());
+
+    RunSnapshot {
+        elapsed: world.resource::<ModeState>().elapsed,
+        wave: world.resource::<WaveState>().current_wave,
+        money: world.resource::<Wallet>().money,
+        players,
+        zombies,
+    }
+}
+
+// Clicks the Watch button of the newest replay on the Replays screen
+fn watch_newest_replay(app: &mut TestApp) {
+    app.world_mut()
+        .resource_mut::<NextState<DashboardScreen>>()
+        .set(DashboardScreen::Replays);
+    app.update();
+
+    let world = app.world_mut();
+    let labels: Vec<(Entity, String)> = world
+        .query::<(&Parent, &Text)>()
+        .iter(world)
+        .map(|(parent, text)| (parent.get(), text.sections[0].value.clone()))
+        .collect();
+    let (watch, _) = labels
+        .into_iter()
+        .find(|(_, label)| label == "Watch")
+        .expect("the replay should be listed");
+    *world.get_mut::<Interaction>(watch).unwrap() = Interaction::Pressed;
+    app.update();
+}
+
+#[test]
+fn a_recorded_run_plays_back_to_the_same_state() {
+    let save_dir = std::env::temp_dir().join(format!("zombie-replay-test-{}", std::process::id()));
+    // SAFETY: this is the only test in the binary, nothing else reads the
+    // environment while it is set
+    unsafe { std::env::set_var("ZOMBIE_SAVE_DIR", &save_dir) };
+
+    let mut app = gameplay_app();
+    app.add_plugins((BotPlugin, ReplayPlugin))
+        .insert_resource(Bot { enabled: true });
+    let mut app = TestApp::build(app);
+
+    // The bot fights into the second wave, then the run is quit
+    app.start_run(Difficulty::Medium, GameMode::ClearWaves);
+    app.advance(30.0);
+    assert_eq!(app.state(), GameState::Playing);
+    let recorded = snapshot(&mut app);
+    assert!(!recorded.zombies.is_empty());
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Dashboard);
+    app.update();
+
+    let entry = &app.world().resource::<ReplayLibrary>().entries[0];
+    let replay: Replay = zombie::storage::load(&entry.file).expect("the replay should be saved");
+
+    // The frame after the click enters Playing with the first recorded
+    // frame, then every update plays one more
+    watch_newest_replay(&mut app);
+    app.update();
+    assert_eq!(app.state(), GameState::Playing);
+    for _ in 1..replay.frames.len() {
+        app.update();
+    }
+    let played = snapshot(&mut app);
+
+    let _ = std::fs::remove_dir_all(&save_dir);
+    assert_eq!(played, recorded);
+}
@@ -0,0 +1,310 @@
+// Shared by the integration tests. Each test crate uses a different part of it.
+#![allow(dead_code)]
+
+use bevy::app::Plugins;
+use bevy::input::ButtonState;
+use bevy::input::gamepad::{
+    GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadConnection, GamepadConnectionEvent,
+    GamepadEvent, GamepadInfo,
+};
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::mouse::MouseButtonInput;
+use bevy::input::touch::{TouchInput, TouchPhase};
+use bevy::prelude::*;
+use zombie::components::{Collider, Health, LevelGeometry, Wall, ZombieTarget};
+use zombie::headless::{gameplay_app, windowless_app};
+use zombie::plugins::controls::PlayerInput;
+use zombie::plugins::enemy::{ZombieArchetype, ZombieScaling, spawn_zombie};
+use zombie::plugins::game_mode::GameMode;
+use zombie::plugins::player::{Player, PlayerId, Speed, WeaponStats, WeaponType};
+use zombie::plugins::settings::{Difficulty, GameSettings};
+use zombie::plugins::simulation::{Interpolated, TICK_RATE};
+use zombie::states::{GameState, WavePhase};
+
+// Every run in the tests plays with this seed, so failures reproduce
+pub const SEED: u64 = 0;
+
+// A windowless game driven one tick at a time. Input goes in as keyboard,
+// mouse, gamepad and touch events, so it takes the same path as a real key press.
+pub struct TestApp {
+    pub app: App,
+}
+
+impl TestApp {
+    // Every gameplay plugin, as in a headless run
+    pub fn gameplay() -> Self {
+        Self::build(gameplay_app())
+    }
+
+    // Only the given plugins, on top of states, input, time and a camera
+    pub fn with_plugins<M>(plugins: impl Plugins<M>) -> Self {
+        let mut app = windowless_app();
+        app.add_plugins(plugins);
+        Self::build(app)
+    }
+
+    // Finishes an app that already has its plugins and settles it on the dashboard
+    pub fn build(mut app: App) -> Self {
+        app.finish();
+        app.cleanup();
+        let mut test_app = Self { app };
+        test_app.update();
+        test_app
+    }
+
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    // Leaves the dashboard the way the Start button does
+    pub fn start_run(&mut self, difficulty: Difficulty, game_mode: GameMode) {
+        let world = self.world_mut();
+        let mut settings = world.get_resource_or_insert_with(GameSettings::default);
+        settings.difficulty = Some(difficulty);
+        settings.game_mode = game_mode;
+        settings.seed = Some(SEED);
+        world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        self.update();
+        assert_eq!(self.state(), GameState::Playing);
+    }
+
+    pub fn state(&self) -> GameState {
+        *self.world().resource::<State<GameState>>().get()
+    }
+
+    pub fn wave_phase(&self) -> Option<WavePhase> {
+        self.world()
+            .get_resource::<State<WavePhase>>()
+            .map(|phase| *phase.get())
+    }
+
+    // One frame, which is one simulation tick
+    pub fn update(&mut self) {
+        self.app.update();
+    }
+
+    pub fn advance(&mut self, seconds: f32) {
+        for _ in 0..ticks(seconds) {
+            self.update();
+        }
+    }
+
+    // Steps until the condition holds, false if it didn't within the time limit
+    pub fn advance_until(
+        &mut self,
+        max_seconds: f32,
+        mut condition: impl FnMut(&mut TestApp) -> bool,
+    ) -> bool {
+        for _ in 0..ticks(max_seconds) {
+            if condition(self) {
+                return true;
+            }
+            self.update();
+        }
+        condition(self)
+    }
+
+    pub fn advance_until_state(&mut self, state: GameState, max_seconds: f32) -> bool {
+        self.advance_until(max_seconds, |app| app.state() == state)
+    }
+
+    pub fn press_key(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Pressed);
+    }
+
+    pub fn release_key(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Released);
+    }
+
+    // Press and release across two frames, like a quick tap
+    pub fn tap_key(&mut self, key: KeyCode) {
+        self.press_key(key);
+        self.update();
+        self.release_key(key);
+        self.update();
+    }
+
+    pub fn press_mouse(&mut self, button: MouseButton) {
+        self.send_mouse(button, ButtonState::Pressed);
+    }
+
+    pub fn release_mouse(&mut self, button: MouseButton) {
+        self.send_mouse(button, ButtonState::Released);
+    }
+
+    // Plugs in a pad, ready on the next frame
+    pub fn connect_gamepad(&mut self) -> Gamepad {
+        let gamepad = Gamepad::new(0);
+        self.world_mut()
+            .send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(
+                gamepad,
+                GamepadConnection::Connected(GamepadInfo {
+                    name: "Test Pad".to_string(),
+                }),
+            )));
+        self.update();
+        gamepad
+    }
+
+    pub fn press_pad(&mut self, gamepad: Gamepad, button: GamepadButtonType) {
+        self.send_pad_button(gamepad, button, 1.0);
+    }
+
+    pub fn release_pad(&mut self, gamepad: Gamepad, button: GamepadButtonType) {
+        self.send_pad_button(gamepad, button, 0.0);
+    }
+
+    pub fn tap_pad(&mut self, gamepad: Gamepad, button: GamepadButtonType) {
+        self.press_pad(gamepad, button);
+        self.update();
+        self.release_pad(gamepad, button);
+        self.update();
+    }
+
+    // Holds a stick axis at a value between -1 and 1
+    pub fn move_stick(&mut self, gamepad: Gamepad, axis: GamepadAxisType, value: f32) {
+        self.world_mut()
+            .send_event(GamepadEvent::Axis(GamepadAxisChangedEvent::new(
+                gamepad, axis, value,
+            )));
+    }
+
+    // Finger `id` goes down, moves and lifts at positions in window pixels
+    pub fn touch_start(&mut self, id: u64, position: Vec2) {
+        self.send_touch(id, TouchPhase::Started, position);
+    }
+
+    pub fn touch_move(&mut self, id: u64, position: Vec2) {
+        self.send_touch(id, TouchPhase::Moved, position);
+    }
+
+    pub fn touch_end(&mut self, id: u64, position: Vec2) {
+        self.send_touch(id, TouchPhase::Ended, position);
+    }
+
+    fn send_touch(&mut self, id: u64, phase: TouchPhase, position: Vec2) {
+        self.world_mut().send_event(TouchInput {
+            phase,
+            position,
+            window: Entity::PLACEHOLDER,
+            force: None,
+            id,
+        });
+    }
+
+    fn send_pad_button(&mut self, gamepad: Gamepad, button: GamepadButtonType, value: f32) {
+        self.world_mut()
+            .send_event(GamepadEvent::Button(GamepadButtonChangedEvent::new(
+                gamepad, button, value,
+            )));
+    }
+
+    fn send_key(&mut self, key_code: KeyCode, state: ButtonState) {
+        self.world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(bevy::input::keyboard::NativeKey::Unidentified),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    fn send_mouse(&mut self, button: MouseButton, state: ButtonState) {
+        self.world_mut().send_event(MouseButtonInput {
+            button,
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    // A pistol-wielding player with 100 health, facing right. Spawn it before
+    // `start_run` and the player plugin keeps it instead of making its own.
+    pub fn spawn_player(&mut self, position: Vec2) -> Entity {
+        self.spawn_coop_player(0, position)
+    }
+
+    // Same as `spawn_player`, for player `index` in a co-op run
+    pub fn spawn_coop_player(&mut self, index: usize, position: Vec2) -> Entity {
+        self.world_mut()
+            .spawn((
+                SpatialBundle::from_transform(Transform::from_translation(position.extend(1.0))),
+                Sprite {
+                    custom_size: Some(Vec2::splat(10.0)),
+                    ..default()
+                },
+                Player,
+                PlayerId(index),
+                PlayerInput::default(),
+                ZombieTarget,
+                Interpolated::at(position),
+                Speed(150.0),
+                Health {
+                    current: 100.0,
+                    max: 100.0,
+                },
+                WeaponStats {
+                    fire_rate: 0.5,
+                    timer: Timer::from_seconds(0.5, TimerMode::Repeating),
+                    current_ammo: 30,
+                    max_ammo: 30,
+                    current_weapon: WeaponType::Pistol,
+                },
+            ))
+            .id()
+    }
+
+    pub fn spawn_zombie(&mut self, archetype: ZombieArchetype, position: Vec2) -> Entity {
+        let world = self.world_mut();
+        let entity = spawn_zombie(
+            &mut world.commands(),
+            archetype,
+            position,
+            ZombieScaling::default(),
+        );
+        world.flush();
+        entity
+    }
+
+    // Level geometry, so it goes away with the rest of the map between runs
+    pub fn spawn_wall(&mut self, position: Vec2, size: Vec2) -> Entity {
+        self.world_mut()
+            .spawn((
+                SpatialBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+                Wall,
+                Collider { size },
+                LevelGeometry,
+            ))
+            .id()
+    }
+
+    pub fn player(&mut self) -> Entity {
+        self.world_mut()
+            .query_filtered::<Entity, With<Player>>()
+            .single(self.app.world())
+    }
+
+    pub fn player_position(&mut self) -> Vec2 {
+        let player = self.player();
+        self.world()
+            .get::<Transform>(player)
+            .expect("Player has no Transform")
+            .translation
+            .truncate()
+    }
+
+    pub fn count<C: Component>(&mut self) -> usize {
+        self.world_mut()
+            .query_filtered::<(), With<C>>()
+            .iter(self.app.world())
+            .count()
+    }
+}
+
+fn ticks(seconds: f32) -> u32 {
+    (seconds * TICK_RATE as f32).ceil() as u32
+}